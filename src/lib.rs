@@ -1,4 +1,13 @@
 pub mod physics;
 
-pub use physics::bodies::{Body, Material, BodyType, Shape};
+pub use physics::aerodynamics::{aerodynamic_force, Aerodynamics};
+pub use physics::bodies::{AnisotropicFriction, Body, BodyDefaults, CombineRule, LockedAxes, Material, BodyType, Shape, ShapeError, CompoundChild, MassProperties};
+pub use physics::decomposition::{decompose_polygon, DecompositionError};
+pub use physics::fields::{Falloff, ForceField, PointField, Turbulence, Vortex, Wind};
+pub use physics::fluids::{FluidError, FluidVolume, Submerged};
+pub use physics::joints::{Joint, PulleyJoint, PrismaticJoint, RevoluteJoint, GearJoint};
+pub use physics::collisions::Aabb;
+pub use physics::materials::{validate_material, MaterialError, MaterialLibrary};
+pub use physics::nbody::NBodyGravity;
+pub use physics::queries::{distance, time_of_impact, Distance, RaycastHit, ShapeCastHit, TimeOfImpact};
 pub use physics::World; 
\ No newline at end of file
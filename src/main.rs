@@ -1,6 +1,4 @@
 // Entry point - Handles simulation loop and init
-mod physics;
-
 use ggez::{
     event,
     graphics::{self, Color, DrawMode, DrawParam, Mesh},
@@ -8,7 +6,7 @@ use ggez::{
     input::mouse::MouseButton,
 };
 use nalgebra::Point2;
-use physics::bodies::{Body, Material, BodyType, Shape};
+use physics::{Body, Material, BodyType, Shape, World};
 
 const WINDOW_WIDTH: f32 = 800.0;
 const WINDOW_HEIGHT: f32 = 600.0;
@@ -19,12 +17,12 @@ const STATIC_COLOR: Color = Color::new(0.5, 0.5, 0.5, 1.0);  // Gray
 const WOOD_COLOR: Color = Color::new(0.545, 0.271, 0.075, 1.0);  // Brown
 
 struct MainState {
-    world: physics::World,
+    world: World,
 }
 
 impl MainState {
     fn new() -> GameResult<MainState> {
-        let mut world = physics::World::new();
+        let mut world = World::new();
         
         // Add ground
         let ground = Body::new_rectangle(
@@ -72,42 +70,7 @@ impl event::EventHandler<ggez::GameError> for MainState {
             // Assuming WINDOW_HEIGHT is a constant defined in this file.
             let screen_y = WINDOW_HEIGHT - physics_y; 
 
-            match &body.shape {
-                Shape::Circle { radius } => {
-                    let r = *radius as f32;
-                    let circle = Mesh::new_circle(
-                        ctx,
-                        DrawMode::fill(),
-                        [physics_x, screen_y], // Use converted screen_y
-                        r,
-                        0.1, // Tolerance
-                        match body.body_type {
-                            BodyType::Static => STATIC_COLOR,
-                            BodyType::Dynamic => Color::WHITE, // Simple white for dynamic circles
-                        },
-                    )?;
-                    canvas.draw(&circle, DrawParam::default());
-                }
-                Shape::Rectangle { width, height } => {
-                    let w = *width as f32;
-                    let h = *height as f32;
-                    let rect = Mesh::new_rectangle(
-                        ctx,
-                        DrawMode::fill(),
-                        graphics::Rect::new(
-                            physics_x - w / 2.0,
-                            screen_y - h / 2.0, // Use converted screen_y
-                            w,
-                            h,
-                        ),
-                        match body.body_type {
-                            BodyType::Static => STATIC_COLOR,
-                            BodyType::Dynamic => WOOD_COLOR,
-                        },
-                    )?;
-                    canvas.draw(&rect, DrawParam::default());
-                }
-            }
+            draw_shape(ctx, &mut canvas, &body.shape, &body.body_type, physics_x, screen_y)?;
         }
 
         canvas.finish(ctx)?;
@@ -137,6 +100,127 @@ impl event::EventHandler<ggez::GameError> for MainState {
     }
 }
 
+/// Draws a shape centered at the given screen position
+fn draw_shape(
+    ctx: &mut Context,
+    canvas: &mut graphics::Canvas,
+    shape: &Shape,
+    body_type: &BodyType,
+    physics_x: f32,
+    screen_y: f32,
+) -> GameResult {
+    match shape {
+        Shape::Circle { radius } => {
+            let r = *radius as f32;
+            let circle = Mesh::new_circle(
+                ctx,
+                DrawMode::fill(),
+                [physics_x, screen_y], // Use converted screen_y
+                r,
+                0.1, // Tolerance
+                match body_type {
+                    BodyType::Static => STATIC_COLOR,
+                    BodyType::Dynamic => Color::WHITE, // Simple white for dynamic circles
+                },
+            )?;
+            canvas.draw(&circle, DrawParam::default());
+        }
+        Shape::Rectangle { width, height } => {
+            let w = *width as f32;
+            let h = *height as f32;
+            let rect = Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                graphics::Rect::new(
+                    physics_x - w / 2.0,
+                    screen_y - h / 2.0, // Use converted screen_y
+                    w,
+                    h,
+                ),
+                match body_type {
+                    BodyType::Static => STATIC_COLOR,
+                    BodyType::Dynamic => WOOD_COLOR,
+                },
+            )?;
+            canvas.draw(&rect, DrawParam::default());
+        }
+        Shape::Capsule { half_length, radius } => {
+            let r = *radius as f32;
+            let hl = *half_length as f32;
+            let capsule = Mesh::new_rounded_rectangle(
+                ctx,
+                DrawMode::fill(),
+                graphics::Rect::new(
+                    physics_x - r,
+                    screen_y - hl - r, // Use converted screen_y
+                    2.0 * r,
+                    2.0 * (hl + r),
+                ),
+                r,
+                match body_type {
+                    BodyType::Static => STATIC_COLOR,
+                    BodyType::Dynamic => WOOD_COLOR,
+                },
+            )?;
+            canvas.draw(&capsule, DrawParam::default());
+        }
+        Shape::Polygon { vertices } => {
+            let points: Vec<[f32; 2]> = vertices
+                .iter()
+                .map(|v| [physics_x + v.x as f32, screen_y - v.y as f32])
+                .collect();
+            let polygon = Mesh::new_polygon(
+                ctx,
+                DrawMode::fill(),
+                &points,
+                match body_type {
+                    BodyType::Static => STATIC_COLOR,
+                    BodyType::Dynamic => WOOD_COLOR,
+                },
+            )?;
+            canvas.draw(&polygon, DrawParam::default());
+        }
+        Shape::Segment { a, b } => {
+            let points = [
+                [physics_x + a.x as f32, screen_y - a.y as f32],
+                [physics_x + b.x as f32, screen_y - b.y as f32],
+            ];
+            let line = Mesh::new_line(ctx, &points, 2.0, STATIC_COLOR)?;
+            canvas.draw(&line, DrawParam::default());
+        }
+        Shape::Chain { vertices } => {
+            let points: Vec<[f32; 2]> = vertices
+                .iter()
+                .map(|v| [physics_x + v.x as f32, screen_y - v.y as f32])
+                .collect();
+            let line = Mesh::new_line(ctx, &points, 2.0, STATIC_COLOR)?;
+            canvas.draw(&line, DrawParam::default());
+        }
+        Shape::HeightField { heights, spacing } => {
+            let points: Vec<[f32; 2]> = heights
+                .iter()
+                .enumerate()
+                .map(|(i, h)| [physics_x + (i as f64 * spacing) as f32, screen_y - *h as f32])
+                .collect();
+            let line = Mesh::new_line(ctx, &points, 2.0, STATIC_COLOR)?;
+            canvas.draw(&line, DrawParam::default());
+        }
+        Shape::Compound { children } => {
+            for child in children {
+                draw_shape(
+                    ctx,
+                    canvas,
+                    &child.shape,
+                    body_type,
+                    physics_x + child.offset.x as f32,
+                    screen_y - child.offset.y as f32,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn main() -> GameResult {
     let cb = ggez::ContextBuilder::new("physics", "rust")
         .window_setup(ggez::conf::WindowSetup::default().title("Physics Engine"))
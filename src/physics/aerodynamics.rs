@@ -0,0 +1,162 @@
+//! Air drag and lift on individual bodies
+
+use nalgebra::{Point2, Vector2};
+use crate::physics::bodies::{Body, Shape};
+use crate::physics::gjk::ConvexCore;
+
+/// Aerodynamic coefficients of a body. Drag grows with the squared speed and the width the
+/// body presents to the air; lift pushes flat shapes sideways depending on their angle of attack.
+/// Like collisions, this takes shapes as defined, not rotated by the body's angle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aerodynamics {
+    /// Drag coefficient (about 1.2 for a flat plate broadside on, 0.5 for a ball)
+    pub drag_coefficient: f64,
+    /// Lift per radian of angle of attack at small angles (2π for a thin plate); zero disables lift
+    pub lift_slope: f64,
+    /// Chord direction along which the shape is flat
+    pub chord: Vector2<f64>,
+}
+
+impl Aerodynamics {
+    /// Drag only, for projectiles
+    pub fn drag(drag_coefficient: f64) -> Self {
+        Self {
+            drag_coefficient,
+            lift_slope: 0.0,
+            chord: Vector2::x(),
+        }
+    }
+
+    /// Drag and thin plate lift along the x axis, for leaves and gliders
+    pub fn flat_plate(drag_coefficient: f64) -> Self {
+        Self {
+            drag_coefficient,
+            lift_slope: 2.0 * std::f64::consts::PI,
+            chord: Vector2::x(),
+        }
+    }
+
+    /// Sets the lift slope
+    pub fn with_lift_slope(mut self, lift_slope: f64) -> Self {
+        self.lift_slope = lift_slope;
+        self
+    }
+
+    /// Sets the chord direction
+    pub fn with_chord(mut self, chord: Vector2<f64>) -> Self {
+        self.chord = chord.normalize();
+        self
+    }
+}
+
+/// Drag and lift on `body` moving through still air of the given density, or zero if it has no
+/// [`Aerodynamics`]
+pub fn aerodynamic_force(body: &Body, air_density: f64) -> Vector2<f64> {
+    let Some(aerodynamics) = body.aerodynamics else {
+        return Vector2::zeros();
+    };
+    let speed = body.velocity.norm();
+    if speed < 1e-12 || air_density <= 0.0 {
+        return Vector2::zeros();
+    }
+    let direction = body.velocity / speed;
+    let dynamic_pressure = 0.5 * air_density * speed * speed;
+
+    let across = Vector2::new(-direction.y, direction.x);
+    let cross_section = projected_width(&body.shape, across);
+    let drag = -direction * dynamic_pressure * aerodynamics.drag_coefficient * cross_section;
+
+    if aerodynamics.lift_slope == 0.0 {
+        return drag;
+    }
+    // Point the chord forwards so the angle of attack lies in [-90°, 90°]
+    let mut chord = aerodynamics.chord;
+    if chord.dot(&direction) < 0.0 {
+        chord = -chord;
+    }
+    let (sin_attack, cos_attack) = (direction.x * chord.y - direction.y * chord.x, direction.dot(&chord));
+    let chord_length = projected_width(&body.shape, aerodynamics.chord);
+    // Thin plate lift, falling off again past 45° so a broadside plate only feels drag
+    let lift_coefficient = aerodynamics.lift_slope * sin_attack * cos_attack;
+    drag + across * dynamic_pressure * lift_coefficient * chord_length
+}
+
+/// Width of a shape measured along `axis` (a unit vector)
+fn projected_width(shape: &Shape, axis: Vector2<f64>) -> f64 {
+    let (min, max) = projected_range(shape, Vector2::zeros(), axis);
+    (max - min).max(0.0)
+}
+
+fn projected_range(shape: &Shape, offset: Vector2<f64>, axis: Vector2<f64>) -> (f64, f64) {
+    if let Shape::Compound { children } = shape {
+        return children.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), child| {
+            let (child_min, child_max) = projected_range(&child.shape, offset + child.offset, axis);
+            (min.min(child_min), max.max(child_max))
+        });
+    }
+    match ConvexCore::from_shape(shape, Point2::from(offset)) {
+        Some(core) => (
+            core.support(-axis).coords.dot(&axis) - core.radius,
+            core.support(axis).coords.dot(&axis) + core.radius,
+        ),
+        None => (f64::INFINITY, f64::NEG_INFINITY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_4, PI, SQRT_2};
+    use crate::physics::bodies::{BodyType, Material};
+
+    #[test]
+    fn test_drag_uses_cross_section() {
+        let mut ball = Body::new_circle(Point2::origin(), 0.5, Material::wood(), BodyType::Dynamic);
+        ball.aerodynamics = Some(Aerodynamics::drag(0.5));
+        ball.velocity = Vector2::new(0.0, -4.0);
+        let force = aerodynamic_force(&ball, 2.0);
+        assert!((force - Vector2::new(0.0, 0.5 * 2.0 * 16.0 * 0.5 * 1.0)).norm() < 1e-10);
+        assert_eq!(aerodynamic_force(&ball, 0.0), Vector2::zeros());
+
+        // A plank falling flat presents its full width, edge on only its thickness
+        let mut plank = Body::new_rectangle(Point2::origin(), 2.0, 0.1, Material::wood(), BodyType::Dynamic);
+        plank.aerodynamics = Some(Aerodynamics::drag(1.0));
+        plank.velocity = Vector2::new(0.0, -1.0);
+        assert!((aerodynamic_force(&plank, 1.0).y - 1.0).abs() < 1e-10);
+        plank.velocity = Vector2::new(1.0, 0.0);
+        assert!((aerodynamic_force(&plank, 1.0).x + 0.05).abs() < 1e-10);
+        // Its angle does not turn the shape, so it still moves edge on
+        plank.angle = std::f64::consts::FRAC_PI_2;
+        assert!((aerodynamic_force(&plank, 1.0).x + 0.05).abs() < 1e-10);
+
+        // Bodies without aerodynamics feel nothing
+        plank.aerodynamics = None;
+        assert_eq!(aerodynamic_force(&plank, 1.0), Vector2::zeros());
+    }
+
+    #[test]
+    fn test_lift_depends_on_angle_of_attack() {
+        let mut plate = Body::new_rectangle(Point2::origin(), 1.0, 0.01, Material::wood(), BodyType::Dynamic);
+        plate.aerodynamics = Some(Aerodynamics::flat_plate(0.0));
+        plate.velocity = Vector2::new(2.0, 0.0);
+
+        // Flying along the plate: no lift
+        assert!(aerodynamic_force(&plate, 1.0).norm() < 1e-10);
+
+        // Gliding down to the right at 45°: maximum lift, square to the path and upwards
+        plate.velocity = Vector2::new(SQRT_2, -SQRT_2);
+        let lift = aerodynamic_force(&plate, 1.0);
+        assert!((lift - Vector2::new(1.0, 1.0) / SQRT_2 * 2.0 * PI).norm() < 1e-10);
+        // The body's angle does not tilt the plate
+        plate.angle = FRAC_PI_4;
+        assert!((aerodynamic_force(&plate, 1.0) - lift).norm() < 1e-10);
+
+        // Climbing to the left, the air hits the plate from above, so lift points down
+        plate.velocity = Vector2::new(-SQRT_2, SQRT_2);
+        assert!(aerodynamic_force(&plate, 1.0).y < 0.0);
+
+        // Falling broadside on there is no lift
+        plate.velocity = Vector2::new(0.0, -2.0);
+        assert!(aerodynamic_force(&plate, 1.0).norm() < 1e-10);
+    }
+}
@@ -1,7 +1,9 @@
 /// Collision detection and resolution
 
-use nalgebra::{/* Point2, */ Vector2};
+use std::collections::HashMap;
+use nalgebra::{Point2, Vector2};
 use crate::physics::bodies::{Body, Shape, BodyType};
+use crate::physics::gjk;
 
 /// Represents a collision between two bodies
 pub struct Collision {
@@ -11,6 +13,45 @@ pub struct Collision {
     pub body_b: usize,
     /// The normal vector of the collision (pointing from body_a to body_b)
     pub normal: Vector2<f64>,
+    /// Penetration depth along the normal
+    pub depth: f64,
+    /// Index of the touching child when body_a has a compound shape
+    pub child_a: Option<usize>,
+    /// Index of the touching child when body_b has a compound shape
+    pub child_b: Option<usize>,
+}
+
+/// Result of a narrow-phase test between two shapes
+pub struct Contact {
+    /// The normal vector of the contact (pointing from the first shape to the second)
+    pub normal: Vector2<f64>,
+    /// Penetration depth along the normal
+    pub depth: f64,
+    /// Index of the touching child when the first shape is a compound
+    pub child_a: Option<usize>,
+    /// Index of the touching child when the second shape is a compound
+    pub child_b: Option<usize>,
+}
+
+impl Contact {
+    pub(crate) fn new(normal: Vector2<f64>, depth: f64) -> Self {
+        Self {
+            normal,
+            depth,
+            child_a: None,
+            child_b: None,
+        }
+    }
+
+    /// Returns the same contact seen from the other shape
+    fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            depth: self.depth,
+            child_a: self.child_b,
+            child_b: self.child_a,
+        }
+    }
 }
 
 /// Detects collisions between bodies and returns a list of collisions
@@ -27,17 +68,22 @@ pub fn detect_collisions(bodies: &[Body]) -> Vec<Collision> {
                 continue;
             }
 
-            if let Some(collision) = check_collision(body_a, body_b, i, j) {
-                collisions.push(collision);
-            }
+            collisions.extend(check_collisions(body_a, body_b, i, j));
         }
     }
     
     collisions
 }
 
-/// Checks for collision between two bodies
-fn check_collision(body_a: &Body, body_b: &Body, index_a: usize, index_b: usize) -> Option<Collision> {
+/// Checks for collision between two bodies, returning the deepest contact
+pub fn check_collision(body_a: &Body, body_b: &Body, index_a: usize, index_b: usize) -> Option<Collision> {
+    check_collisions(body_a, body_b, index_a, index_b)
+        .into_iter()
+        .max_by(|x, y| x.depth.total_cmp(&y.depth))
+}
+
+/// Checks for collisions between two bodies, one per touching pair of compound children
+pub fn check_collisions(body_a: &Body, body_b: &Body, index_a: usize, index_b: usize) -> Vec<Collision> {
     // Ensure index_a is always smaller than index_b for consistent ordering
     let (body1, body2, idx1, idx2) = if index_a < index_b {
         (body_a, body_b, index_a, index_b)
@@ -45,21 +91,81 @@ fn check_collision(body_a: &Body, body_b: &Body, index_a: usize, index_b: usize)
         (body_b, body_a, index_b, index_a)
     };
 
-    let collision_result = match (&body1.shape, &body2.shape) {
+    collide_shapes_all(&body1.shape, body1.position, &body2.shape, body2.position)
+        .into_iter()
+        .map(|contact| Collision {
+            body_a: idx1,
+            body_b: idx2,
+            normal: contact.normal, // Points from 1 to 2
+            depth: contact.depth,
+            child_a: contact.child_a,
+            child_b: contact.child_b,
+        })
+        .collect()
+}
+
+/// Narrow phase for shapes that may be compounds: one contact per touching pair of children
+pub fn collide_shapes_all(
+    shape_a: &Shape,
+    position_a: Point2<f64>,
+    shape_b: &Shape,
+    position_b: Point2<f64>,
+) -> Vec<Contact> {
+    match (shape_a, shape_b) {
+        (Shape::Compound { children }, _) => children
+            .iter()
+            .enumerate()
+            .flat_map(|(index, child)| {
+                collide_shapes_all(&child.shape, position_a + child.offset, shape_b, position_b)
+                    .into_iter()
+                    .map(move |contact| Contact { child_a: Some(index), ..contact })
+            })
+            .collect(),
+        (_, Shape::Compound { .. }) => collide_shapes_all(shape_b, position_b, shape_a, position_a)
+            .into_iter()
+            .map(Contact::flipped)
+            .collect(),
+        _ => collide_shapes(shape_a, position_a, shape_b, position_b).into_iter().collect(),
+    }
+}
+
+/// Narrow phase: tests two shapes placed at the given positions against each other.
+/// For compounds the deepest contact over all children is returned.
+pub fn collide_shapes(
+    shape_a: &Shape,
+    position_a: Point2<f64>,
+    shape_b: &Shape,
+    position_b: Point2<f64>,
+) -> Option<Contact> {
+    match (shape_a, shape_b) {
+        (Shape::Compound { .. }, _) | (_, Shape::Compound { .. }) => {
+            collide_shapes_all(shape_a, position_a, shape_b, position_b)
+                .into_iter()
+                .max_by(|x, y| x.depth.total_cmp(&y.depth))
+        }
+        (Shape::Chain { .. } | Shape::HeightField { .. }, Shape::Chain { .. } | Shape::HeightField { .. }) => None,
+        (Shape::Chain { vertices }, _) => calculate_chain_collision(vertices, position_a, shape_b, position_b),
+        (_, Shape::Chain { vertices }) => {
+            calculate_chain_collision(vertices, position_b, shape_a, position_a).map(Contact::flipped)
+        }
+        (Shape::HeightField { heights, spacing }, _) => {
+            calculate_height_field_collision(heights, *spacing, position_a, shape_b, position_b)
+        }
+        (_, Shape::HeightField { heights, spacing }) => {
+            calculate_height_field_collision(heights, *spacing, position_b, shape_a, position_a)
+                .map(Contact::flipped)
+        }
         (Shape::Circle { radius: r1 }, Shape::Circle { radius: r2 }) => {
             // Circle-Circle collision
-            let diff = body2.position - body1.position;
+            let diff = position_b - position_a;
             let distance = diff.norm();
             let min_distance = r1 + r2;
             
             if distance < min_distance && distance > 1e-10 {
-                let normal = diff / distance;
-                
-                Some(Collision {
-                    body_a: idx1,
-                    body_b: idx2,
-                    normal, // Points from 1 to 2
-                })
+                Some(Contact::new(
+                    diff / distance, // Points from a to b
+                    min_distance - distance,
+                ))
             } else {
                 None
             }
@@ -69,61 +175,247 @@ fn check_collision(body_a: &Body, body_b: &Body, index_a: usize, index_b: usize)
             let half_size1 = Vector2::new(w1 / 2.0, h1 / 2.0);
             let half_size2 = Vector2::new(w2 / 2.0, h2 / 2.0);
             
-            let diff = body2.position - body1.position;
+            let diff = position_b - position_a;
             let abs_diff = Vector2::new(diff.x.abs(), diff.y.abs());
             
             let overlap = half_size1 + half_size2 - abs_diff;
             
             if overlap.x > 0.0 && overlap.y > 0.0 {
-                let normal = if overlap.x < overlap.y {
-                    Vector2::new(diff.x.signum(), 0.0)
+                let (normal, depth) = if overlap.x < overlap.y {
+                    (Vector2::new(diff.x.signum(), 0.0), overlap.x)
                 } else {
-                    Vector2::new(0.0, diff.y.signum())
+                    (Vector2::new(0.0, diff.y.signum()), overlap.y)
                 };
                 
-                Some(Collision {
-                    body_a: idx1,
-                    body_b: idx2,
-                    normal, // Points from 1 to 2
-                })
+                Some(Contact::new(normal, depth)) // Points from a to b
             } else {
                 None
             }
         }
         (Shape::Circle { radius }, Shape::Rectangle { width, height }) => {
-            // Circle (body1) vs Rectangle (body2)
-            calculate_circle_rectangle_collision(body1, body2, idx1, idx2, *radius, *width, *height)
+            // The helper's normal points from the rectangle towards the circle
+            calculate_circle_rectangle_collision(position_a, position_b, *radius, *width, *height)
+                .map(Contact::flipped)
         }
         (Shape::Rectangle { width, height }, Shape::Circle { radius }) => {
-             // Rectangle (body1) vs Circle (body2)
-            // Calculate as Circle-Rect
-            calculate_circle_rectangle_collision(body2, body1, idx2, idx1, *radius, *width, *height)
-                .map(|mut c| {
-                    // Swap bodies back to original order (idx1 < idx2)
-                    c.body_a = idx1;
-                    c.body_b = idx2;
-                    // The normal from calculate_circle_rectangle_collision already points from rect (1) to circle (2).
-                    // DO NOT flip it.
-                    c
-                })
+            calculate_circle_rectangle_collision(position_b, position_a, *radius, *width, *height)
         }
-    };
+        (Shape::Rectangle { .. } | Shape::Polygon { .. }, Shape::Rectangle { .. } | Shape::Polygon { .. }) => {
+            let vertices_a = polygon_vertices(shape_a, position_a)?;
+            let vertices_b = polygon_vertices(shape_b, position_b)?;
+            calculate_polygons_collision(&vertices_a, &vertices_b)
+        }
+        (
+            Shape::Circle { .. } | Shape::Capsule { .. } | Shape::Segment { .. },
+            Shape::Circle { .. } | Shape::Capsule { .. } | Shape::Segment { .. },
+        ) => {
+            let (a1, b1, r1) = rounded_segment(shape_a, position_a)?;
+            let (a2, b2, r2) = rounded_segment(shape_b, position_b)?;
+            calculate_rounded_segments_collision(a1, b1, r1, a2, b2, r2)
+        }
+        // Any other convex pair, e.g. a polygon against a capsule
+        _ => gjk::collide_convex_shapes(shape_a, position_a, shape_b, position_b),
+    }
+}
+
+/// Describes circles, capsules and segments as a world-space core segment swept by a radius
+pub(crate) fn rounded_segment(shape: &Shape, position: Point2<f64>) -> Option<(Point2<f64>, Point2<f64>, f64)> {
+    match shape {
+        Shape::Circle { radius } => Some((position, position, *radius)),
+        Shape::Capsule { half_length, radius } => Some((
+            position - Vector2::new(0.0, *half_length),
+            position + Vector2::new(0.0, *half_length),
+            *radius,
+        )),
+        Shape::Segment { a, b } => Some((position + a.coords, position + b.coords, 0.0)),
+        Shape::Rectangle { .. }
+        | Shape::Polygon { .. }
+        | Shape::Chain { .. }
+        | Shape::Compound { .. }
+        | Shape::HeightField { .. } => None,
+    }
+}
 
-    collision_result
+/// Axis-aligned bounding box in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Point2<f64>,
+    pub max: Point2<f64>,
 }
 
-// Helper function for Circle-Rectangle collision
+impl Aabb {
+    /// Smallest box containing all the given points
+    fn from_points(points: impl IntoIterator<Item = Point2<f64>>) -> Self {
+        points.into_iter().fold(
+            Aabb {
+                min: Point2::new(f64::INFINITY, f64::INFINITY),
+                max: Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            },
+            |aabb, p| aabb.merged(&Aabb { min: p, max: p }),
+        )
+    }
+
+    /// Smallest box containing both boxes
+    pub fn merged(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Point2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Whether the point lies inside the box or on its boundary
+    pub fn contains(&self, point: Point2<f64>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Whether the two boxes overlap
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x && self.min.y <= other.max.y && self.max.y >= other.min.y
+    }
+}
+
+/// Computes the world-space bounding box of a shape placed at `position`
+pub fn compute_aabb(shape: &Shape, position: Point2<f64>) -> Aabb {
+    match shape {
+        Shape::Rectangle { width, height } => {
+            let half_extents = Vector2::new(width / 2.0, height / 2.0);
+            Aabb { min: position - half_extents, max: position + half_extents }
+        }
+        Shape::Chain { vertices } | Shape::Polygon { vertices } => {
+            Aabb::from_points(vertices.iter().map(|v| position + v.coords))
+        }
+        Shape::HeightField { heights, spacing } => Aabb::from_points(
+            heights.iter().enumerate().map(|(i, h)| position + Vector2::new(i as f64 * spacing, *h)),
+        ),
+        Shape::Compound { children } => children
+            .iter()
+            .map(|child| compute_aabb(&child.shape, position + child.offset))
+            .reduce(|a, b| a.merged(&b))
+            .unwrap_or(Aabb { min: position, max: position }),
+        _ => {
+            let (a, b, radius) = rounded_segment(shape, position).expect("rounded shape");
+            let extent = Vector2::new(radius, radius);
+            Aabb {
+                min: Point2::new(a.x.min(b.x), a.y.min(b.y)) - extent,
+                max: Point2::new(a.x.max(b.x), a.y.max(b.y)) + extent,
+            }
+        }
+    }
+}
+
+/// Left-hand normal of the edge from `a` to `b`
+fn edge_normal(a: Point2<f64>, b: Point2<f64>) -> Vector2<f64> {
+    let d = (b - a).normalize();
+    Vector2::new(-d.y, d.x)
+}
+
+fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+// Helper for a chain against any other shape (normal points from the chain towards the shape).
+// Each edge is tested as a segment; contacts reaching around an internal vertex are only kept
+// when they fall inside that vertex's convex normal cone, otherwise the neighbouring edge owns them.
+fn calculate_chain_collision(
+    vertices: &[Point2<f64>],
+    chain_position: Point2<f64>,
+    shape: &Shape,
+    position: Point2<f64>,
+) -> Option<Contact> {
+    let world: Vec<Point2<f64>> = vertices.iter().map(|v| chain_position + v.coords).collect();
+    calculate_polyline_collision(&world, 0..world.len().saturating_sub(1), shape, position)
+}
+
+// Helper for a height field against any other shape (normal points from the terrain towards the shape).
+// Only the columns under the other shape's bounding box are built and tested, with one extra
+// sample on each side so the outer edges still get ghost vertices.
+fn calculate_height_field_collision(
+    heights: &[f64],
+    spacing: f64,
+    field_position: Point2<f64>,
+    shape: &Shape,
+    position: Point2<f64>,
+) -> Option<Contact> {
+    if heights.len() < 2 || spacing <= 0.0 {
+        return None;
+    }
+    let bounds = compute_aabb(shape, position);
+    let last = heights.len() - 1;
+    let first_column = ((bounds.min.x - field_position.x) / spacing).floor().max(0.0) as usize;
+    let last_column = (((bounds.max.x - field_position.x) / spacing).ceil().max(0.0) as usize)
+        .max(first_column + 1)
+        .min(last);
+    if first_column >= last_column {
+        return None;
+    }
+
+    let first_sample = first_column.saturating_sub(1);
+    let last_sample = (last_column + 1).min(last);
+    let world: Vec<Point2<f64>> = (first_sample..=last_sample)
+        .map(|i| field_position + Vector2::new(i as f64 * spacing, heights[i]))
+        .collect();
+    let edges = (first_column - first_sample)..(last_column - first_sample);
+    calculate_polyline_collision(&world, edges, shape, position)
+}
+
+// Tests the given edges of a world-space polyline, using the vertices around them as ghosts
+fn calculate_polyline_collision(
+    world: &[Point2<f64>],
+    edges: std::ops::Range<usize>,
+    shape: &Shape,
+    position: Point2<f64>,
+) -> Option<Contact> {
+    let mut deepest: Option<Contact> = None;
+
+    for i in edges {
+        let (a, b) = (world[i], world[i + 1]);
+        if (b - a).norm_squared() < 1e-12 {
+            continue;
+        }
+        let edge = Shape::Segment { a: Point2::origin(), b: Point2::from(b - a) };
+        let Some(contact) = collide_shapes(&edge, a, shape, position) else {
+            continue;
+        };
+
+        let face = edge_normal(a, b);
+        let facing = contact.normal.dot(&face);
+        if facing.abs() < 1.0 - 1e-9 {
+            // Vertex contact: find which end it wraps around and that end's neighbour (ghost) edge
+            let neighbour_normal = if contact.normal.dot(&(b - a)) < 0.0 {
+                (i > 0).then(|| edge_normal(world[i - 1], a))
+            } else {
+                world.get(i + 2).map(|&next| edge_normal(b, next))
+            };
+
+            if let Some(neighbour) = neighbour_normal {
+                let side = if facing < 0.0 { -1.0 } else { 1.0 };
+                let (u, w) = (face * side, neighbour * side);
+                let span = cross(u, w);
+                let inside_cone = span.abs() > 1e-9
+                    && cross(u, contact.normal) * span >= 0.0
+                    && cross(contact.normal, w) * span >= 0.0;
+                if !inside_cone {
+                    continue;
+                }
+            }
+        }
+
+        if deepest.as_ref().is_none_or(|best| contact.depth > best.depth) {
+            deepest = Some(contact);
+        }
+    }
+
+    deepest
+}
+
+// Helper function for Circle-Rectangle collision (normal points from the rectangle towards the circle)
 fn calculate_circle_rectangle_collision(
-    circle_body: &Body, 
-    rect_body: &Body, 
-    circle_idx: usize, 
-    rect_idx: usize, 
+    circle_center: Point2<f64>,
+    rect_center: Point2<f64>,
     radius: f64, 
     width: f64, 
     height: f64
-) -> Option<Collision> {
-    let circle_center = circle_body.position;
-    let rect_center = rect_body.position;
+) -> Option<Contact> {
     let half_extents = Vector2::new(width / 2.0, height / 2.0);
 
     let delta = circle_center - rect_center;
@@ -137,18 +429,154 @@ fn calculate_circle_rectangle_collision(
 
     if distance_sq < radius_sq && distance_sq > 1e-12 {
         let distance = distance_sq.sqrt();
-        let normal = collision_vector / distance; // Normal points from rect towards circle
 
-        Some(Collision {
-            body_a: circle_idx, // Circle index
-            body_b: rect_idx,   // Rectangle index
-            normal,
-        })
+        Some(Contact::new(
+            collision_vector / distance, // Normal points from rect towards circle
+            radius - distance,
+        ))
     } else {
         None
     }
 }
 
+/// Closest pair of points between segments `a1-b1` and `a2-b2`
+pub(crate) fn closest_points_between_segments(
+    a1: Point2<f64>,
+    b1: Point2<f64>,
+    a2: Point2<f64>,
+    b2: Point2<f64>,
+) -> (Point2<f64>, Point2<f64>) {
+    let d1 = b1 - a1;
+    let d2 = b2 - a2;
+    let r = a1 - a2;
+    let len1 = d1.norm_squared();
+    let len2 = d2.norm_squared();
+    let f = d2.dot(&r);
+
+    let (s, t) = if len1 < 1e-12 && len2 < 1e-12 {
+        (0.0, 0.0)
+    } else if len1 < 1e-12 {
+        (0.0, (f / len2).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(&r);
+        if len2 < 1e-12 {
+            ((-c / len1).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(&d2);
+            let denom = len1 * len2 - b * b;
+            let mut s = if denom > 1e-12 { ((b * f - c * len2) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let mut t = (b * s + f) / len2;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / len1).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / len1).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+
+    (a1 + d1 * s, a2 + d2 * t)
+}
+
+// Helper for pairs of circles, capsules and segments (normal points from the first towards the second)
+fn calculate_rounded_segments_collision(
+    a1: Point2<f64>,
+    b1: Point2<f64>,
+    radius1: f64,
+    a2: Point2<f64>,
+    b2: Point2<f64>,
+    radius2: f64,
+) -> Option<Contact> {
+    let (p1, p2) = closest_points_between_segments(a1, b1, a2, b2);
+    let diff = p2 - p1;
+    let distance = diff.norm();
+    let min_distance = radius1 + radius2;
+
+    if distance >= min_distance {
+        return None;
+    }
+
+    if distance > 1e-10 {
+        return Some(Contact::new(
+            diff / distance,
+            min_distance - distance,
+        ));
+    }
+
+    // The core segments cross: push apart across whichever segment has a direction
+    let axis = if (b1 - a1).norm_squared() > 1e-12 { b1 - a1 } else { b2 - a2 };
+    if axis.norm_squared() < 1e-12 {
+        return None;
+    }
+    let mut normal = Vector2::new(-axis.y, axis.x).normalize();
+    let centers = Point2::from((a2.coords + b2.coords) / 2.0) - Point2::from((a1.coords + b1.coords) / 2.0);
+    if centers.dot(&normal) < 0.0 {
+        normal = -normal;
+    }
+    Some(Contact::new(normal, min_distance))
+}
+
+/// World-space vertices (counter-clockwise) of rectangles and convex polygons
+pub(crate) fn polygon_vertices(shape: &Shape, position: Point2<f64>) -> Option<Vec<Point2<f64>>> {
+    match shape {
+        Shape::Rectangle { width, height } => {
+            let (hw, hh) = (width / 2.0, height / 2.0);
+            Some(vec![
+                position + Vector2::new(-hw, -hh),
+                position + Vector2::new(hw, -hh),
+                position + Vector2::new(hw, hh),
+                position + Vector2::new(-hw, hh),
+            ])
+        }
+        Shape::Polygon { vertices } => Some(vertices.iter().map(|v| position + v.coords).collect()),
+        _ => None,
+    }
+}
+
+/// Outward unit normals of the edges of a counter-clockwise polygon
+fn polygon_normals(vertices: &[Point2<f64>]) -> Vec<Vector2<f64>> {
+    (0..vertices.len())
+        .filter_map(|i| {
+            let edge = vertices[(i + 1) % vertices.len()] - vertices[i];
+            (edge.norm_squared() > 1e-12).then(|| Vector2::new(edge.y, -edge.x).normalize())
+        })
+        .collect()
+}
+
+fn project_points(points: &[Point2<f64>], axis: Vector2<f64>) -> (f64, f64) {
+    points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+        let d = p.coords.dot(&axis);
+        (min.min(d), max.max(d))
+    })
+}
+
+// Helper for two convex polygons using the separating axis test (normal points from the first towards the second)
+fn calculate_polygons_collision(vertices_a: &[Point2<f64>], vertices_b: &[Point2<f64>]) -> Option<Contact> {
+    let mut best: Option<Contact> = None;
+    for axis in polygon_normals(vertices_a).into_iter().chain(polygon_normals(vertices_b)) {
+        let (min_a, max_a) = project_points(vertices_a, axis);
+        let (min_b, max_b) = project_points(vertices_b, axis);
+
+        // Distance the second polygon would have to move along +axis or -axis to separate
+        let push_forward = max_a - min_b;
+        let push_backward = max_b - min_a;
+        if push_forward <= 0.0 || push_backward <= 0.0 {
+            return None;
+        }
+        let (normal, depth) = if push_forward < push_backward {
+            (axis, push_forward)
+        } else {
+            (-axis, push_backward)
+        };
+        if best.as_ref().is_none_or(|contact| depth < contact.depth) {
+            best = Some(Contact::new(normal, depth));
+        }
+    }
+    best
+}
+
 /// Resolves collisions by applying impulses to the bodies
 pub fn resolve_collisions(world: &mut crate::physics::World, collisions: &[Collision]) {
     for collision in collisions {
@@ -161,8 +589,22 @@ pub fn resolve_collisions(world: &mut crate::physics::World, collisions: &[Colli
             continue;
         }
         
+        // Circles and capsules touch at their rounded rim, so their contact point also moves with the
+        // spin. Arms run from the center of mass, which may sit off the shape's center. Other shapes
+        // have no contact point yet, so impulses only act on their linear velocity.
+        let arm = |body: &Body, normal: Vector2<f64>| {
+            rim_point(body, normal).map(|point| point - body.center_of_mass())
+        };
+        let arm_a = arm(body_a, collision.normal);
+        let arm_b = arm(body_b, -collision.normal);
+        // Conveyor surfaces add their tangential velocity at the contact
+        let point_velocity = |body: &Body, arm: Option<Vector2<f64>>| {
+            let surface = body.surface_velocity - collision.normal * body.surface_velocity.dot(&collision.normal);
+            body.velocity + surface + arm.map_or(Vector2::zeros(), |arm| Vector2::new(-arm.y, arm.x) * body.angular_velocity)
+        };
+
         // Calculate relative velocity
-        let relative_velocity = body_b.velocity - body_a.velocity;
+        let relative_velocity = point_velocity(body_b, arm_b) - point_velocity(body_a, arm_a);
         
         // Calculate relative velocity along the normal
         let velocity_along_normal = relative_velocity.dot(&collision.normal);
@@ -172,15 +614,29 @@ pub fn resolve_collisions(world: &mut crate::physics::World, collisions: &[Colli
             continue;
         }
         
-        // Calculate restitution (bounciness)
-        let restitution = (body_a.material.restitution + body_b.material.restitution) / 2.0;
+        // Calculate restitution (bounciness); slow contacts are inelastic so resting bodies do not jitter
+        let restitution = if -velocity_along_normal < world.restitution_threshold {
+            0.0
+        } else {
+            body_a.material.combined_restitution(&body_b.material)
+        };
         
-        // Calculate inverse masses (0 for static bodies)
-        let inv_mass_a = if body_a.body_type == BodyType::Static { 0.0 } else { 1.0 / body_a.mass };
-        let inv_mass_b = if body_b.body_type == BodyType::Static { 0.0 } else { 1.0 / body_b.mass };
-        let total_inv_mass = inv_mass_a + inv_mass_b;
+        // Calculate inverse masses per axis (0 for static bodies and locked axes)
+        let inv_mass_a = body_a.inverse_mass_vector();
+        let inv_mass_b = body_b.inverse_mass_vector();
+        let (inv_inertia_a, inv_inertia_b) = (body_a.inverse_inertia(), body_b.inverse_inertia());
+        let angular_term = |inv_inertia: f64, arm: Option<Vector2<f64>>, direction: Vector2<f64>| {
+            arm.map_or(0.0, |arm| inv_inertia * cross(arm, direction).powi(2))
+        };
+        let effective_inv_mass = |direction: Vector2<f64>| {
+            direction.dot(&inv_mass_a.component_mul(&direction))
+                + direction.dot(&inv_mass_b.component_mul(&direction))
+                + angular_term(inv_inertia_a, arm_a, direction)
+                + angular_term(inv_inertia_b, arm_b, direction)
+        };
+        let total_inv_mass = effective_inv_mass(collision.normal);
 
-        // Ensure we don't divide by zero (shouldn't happen due to the static-static check earlier)
+        // Nothing can move along the normal (both static, or locked on that axis)
         if total_inv_mass == 0.0 {
             continue;
         }
@@ -192,17 +648,23 @@ pub fn resolve_collisions(world: &mut crate::physics::World, collisions: &[Colli
         // Apply impulse using inverse masses
         let impulse = collision.normal * impulse_scalar;
         if body_a.body_type == BodyType::Dynamic {
-            body_a.velocity -= impulse * inv_mass_a;
+            body_a.velocity -= impulse.component_mul(&inv_mass_a);
+            if let Some(arm) = arm_a {
+                body_a.angular_velocity -= inv_inertia_a * cross(arm, impulse);
+            }
         }
         if body_b.body_type == BodyType::Dynamic {
-            body_b.velocity += impulse * inv_mass_b;
+            body_b.velocity += impulse.component_mul(&inv_mass_b);
+            if let Some(arm) = arm_b {
+                body_b.angular_velocity += inv_inertia_b * cross(arm, impulse);
+            }
         }
 
         // Friction Impulse Calculation
         let friction_tolerance = 1e-7;
 
         // Recalculate relative velocity AFTER normal impulse is applied
-        let relative_velocity_friction = body_b.velocity - body_a.velocity; 
+        let relative_velocity_friction = point_velocity(body_b, arm_b) - point_velocity(body_a, arm_a);
 
         // Project relative velocity onto the normal vector
         let velocity_normal_comp = collision.normal * relative_velocity_friction.dot(&collision.normal);
@@ -216,33 +678,170 @@ pub fn resolve_collisions(world: &mut crate::physics::World, collisions: &[Colli
             let tangent_direction = velocity_tangent_comp / tangential_speed;
 
             // Calculate impulse magnitude needed to stop tangential motion
-            let jt = -tangential_speed / total_inv_mass;
+            let tangent_inv_mass = effective_inv_mass(tangent_direction);
 
-            // Calculate static friction limit
-            let mu_static = (body_a.material.friction + body_b.material.friction) / 2.0;
-            let max_friction_impulse = mu_static * impulse_scalar.abs();
+            if tangent_inv_mass > 0.0 {
+                let jt = -tangential_speed / tangent_inv_mass;
 
-            // Clamp friction impulse magnitude by the static friction limit
-            let friction_impulse_scalar = jt.clamp(-max_friction_impulse, max_friction_impulse);
+                // Stick if the static friction limit can stop the sliding, otherwise slide with kinetic friction
+                let (mu_static, mu_kinetic) =
+                    body_a.material.combined_friction_along(&body_b.material, tangent_direction);
+                let friction_impulse_scalar = if jt.abs() <= mu_static * impulse_scalar.abs() {
+                    jt
+                } else {
+                    jt.signum() * mu_kinetic * impulse_scalar.abs()
+                };
 
-            // Calculate final friction impulse vector
-            let friction_impulse = tangent_direction * friction_impulse_scalar;
+                // Calculate final friction impulse vector
+                let friction_impulse = tangent_direction * friction_impulse_scalar;
 
-            // Apply friction impulse
-            if body_a.body_type == BodyType::Dynamic {
-                body_a.velocity -= friction_impulse * inv_mass_a;
+                // Apply friction impulse
+                if body_a.body_type == BodyType::Dynamic {
+                    body_a.velocity -= friction_impulse.component_mul(&inv_mass_a);
+                    if let Some(arm) = arm_a {
+                        body_a.angular_velocity -= inv_inertia_a * cross(arm, friction_impulse);
+                    }
+                }
+                if body_b.body_type == BodyType::Dynamic {
+                    body_b.velocity += friction_impulse.component_mul(&inv_mass_b);
+                    if let Some(arm) = arm_b {
+                        body_b.angular_velocity += inv_inertia_b * cross(arm, friction_impulse);
+                    }
+                }
             }
-            if body_b.body_type == BodyType::Dynamic {
-                body_b.velocity += friction_impulse * inv_mass_b;
+        }
+
+        // Rolling resistance: each rounded body gets a torque against its own rolling over the other
+        // surface, bounded by the normal impulse times its radius like Coulomb friction. It rolls
+        // when it spins relative to the contact normal, which turns as two rounded bodies roll
+        // around each other but stays put against flat surfaces (shapes are not rotated).
+        let rolling_resistance = body_a.material.combined_rolling_resistance(&body_b.material);
+        if rolling_resistance > 0.0 {
+            let (radius_a, radius_b) = (rolling_radius(body_a), rolling_radius(body_b));
+            let normal_spin = match (radius_a, radius_b) {
+                (Some(radius_a), Some(radius_b)) => {
+                    cross(collision.normal, body_b.velocity - body_a.velocity) / (radius_a + radius_b)
+                }
+                _ => 0.0,
+            };
+            for (body, radius, inv_inertia) in [(body_a, radius_a, inv_inertia_a), (body_b, radius_b, inv_inertia_b)] {
+                let Some(radius) = radius else {
+                    continue;
+                };
+                if inv_inertia == 0.0 {
+                    continue;
+                }
+                let max_impulse = rolling_resistance * radius * impulse_scalar.abs();
+                let rolling = body.angular_velocity - normal_spin;
+                let angular_impulse = (-rolling / inv_inertia).clamp(-max_impulse, max_impulse);
+                body.angular_velocity += inv_inertia * angular_impulse;
             }
         }
     }
 }
 
+/// Minimum alignment of a contact normal with a one-way body's direction for the contact to block
+const ONE_WAY_ALIGNMENT: f64 = 0.5;
+
+/// Updates the `(one-way body, other body)` pairs in contact and whether each is passing through.
+/// When a contact with a one-way body starts, it becomes a pass if the other body touches it from a
+/// side other than the one its direction points to, or is moving along that direction, e.g. jumping
+/// up into a platform deep enough that the contact normal already points up. That choice holds
+/// until the two stop overlapping, so a body halfway through a platform is not pushed out of either
+/// side and one bouncing on top does not drop through.
+///
+/// Pairs are kept by body index, so a pair is also dropped once the first index no longer holds a
+/// one-way body, e.g. after `World::bodies` was edited.
+pub fn update_one_way_contacts(bodies: &[Body], collisions: &[Collision], contacts: &mut HashMap<(usize, usize), bool>) {
+    contacts.retain(|&(one_way, other), _| {
+        bodies.get(one_way).is_some_and(|body| body.one_way.is_some())
+            && collisions.iter().any(|collision| {
+                (collision.body_a, collision.body_b) == (one_way, other) || (collision.body_a, collision.body_b) == (other, one_way)
+            })
+    });
+
+    for collision in collisions {
+        // Normals point from the one-way body towards the other body
+        for (one_way, other, normal) in [
+            (collision.body_a, collision.body_b, collision.normal),
+            (collision.body_b, collision.body_a, -collision.normal),
+        ] {
+            let Some(direction) = bodies[one_way].one_way else {
+                continue;
+            };
+            contacts.entry((one_way, other)).or_insert_with(|| {
+                let direction = direction.normalize();
+                let approach_velocity = bodies[other].velocity - bodies[one_way].velocity;
+                normal.dot(&direction) < ONE_WAY_ALIGNMENT || approach_velocity.dot(&direction) > 0.0
+            });
+        }
+    }
+}
+
+/// Drops the contacts of pairs that are passing through a one-way body
+pub fn remove_one_way_passes(collisions: &mut Vec<Collision>, contacts: &HashMap<(usize, usize), bool>) {
+    let passing = |pair| contacts.get(&pair).copied().unwrap_or(false);
+    if contacts.values().any(|&passing| passing) {
+        collisions.retain(|collision| {
+            !passing((collision.body_a, collision.body_b)) && !passing((collision.body_b, collision.body_a))
+        });
+    }
+}
+
+/// Penetration allowed before positions are corrected, keeping resting contacts touching
+const LINEAR_SLOP: f64 = 0.005;
+/// Fraction of the remaining penetration removed per step
+const CORRECTION_PERCENT: f64 = 0.8;
+
+/// Moves overlapping bodies apart along the contact normal, split by inverse mass.
+/// Only positions change, so this removes sinking without adding energy.
+pub fn correct_positions(bodies: &mut [Body], collisions: &[Collision]) {
+    for collision in collisions {
+        let (first, second) = bodies.split_at_mut(collision.body_a + 1);
+        let body_a = &mut first[collision.body_a];
+        let body_b = &mut second[collision.body_b - collision.body_a - 1];
+
+        let inv_mass_a = body_a.inverse_mass_vector();
+        let inv_mass_b = body_b.inverse_mass_vector();
+        let normal = collision.normal;
+        let total_inv_mass = normal.dot(&inv_mass_a.component_mul(&normal)) + normal.dot(&inv_mass_b.component_mul(&normal));
+        let penetration = collision.depth - LINEAR_SLOP;
+        if total_inv_mass == 0.0 || penetration <= 0.0 {
+            continue;
+        }
+
+        let correction = normal * (penetration * CORRECTION_PERCENT / total_inv_mass);
+        body_a.position -= correction.component_mul(&inv_mass_a);
+        body_b.position += correction.component_mul(&inv_mass_b);
+    }
+}
+
+/// Radius of the rounded rim a circle or capsule rolls on
+fn rolling_radius(body: &Body) -> Option<f64> {
+    match body.shape {
+        Shape::Circle { radius } | Shape::Capsule { radius, .. } => Some(radius),
+        _ => None,
+    }
+}
+
+/// World-space point where a circle or capsule touches a surface lying along `normal` from it.
+/// A capsule touching with its straight side presses along the whole side, so it acts level with the center.
+fn rim_point(body: &Body, normal: Vector2<f64>) -> Option<Point2<f64>> {
+    let radius = rolling_radius(body)?;
+    let core = match body.shape {
+        Shape::Capsule { half_length, .. } if normal.y.abs() > 1e-6 => {
+            body.position + Vector2::new(0.0, half_length * normal.y.signum())
+        }
+        _ => body.position,
+    };
+    Some(core + normal * radius)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::physics::World;
+    use crate::physics::bodies::{CombineRule, CompoundChild, Material};
 
     #[test]
     fn test_circle_circle_collision() {
@@ -315,6 +914,241 @@ mod tests {
         assert!((collision.depth - 1.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_circle_rectangle_normal_order() {
+        // Circle stored first: the normal must still point from body_a (circle) to body_b (rectangle)
+        let circle = Body::new_circle(Point2::new(-2.5, 0.0), 1.0, Material::wood(), BodyType::Dynamic);
+        let rect = Body::new_rectangle(Point2::new(0.0, 0.0), 4.0, 4.0, Material::wood(), BodyType::Static);
+
+        let collision = check_collision(&circle, &rect, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, 0.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_capsule_circle_collision() {
+        let capsule = Body::new_capsule(Point2::new(0.0, 0.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+        // Beside the straight part of the capsule
+        let side = Body::new_circle(Point2::new(1.5, 1.0), 1.0, Material::wood(), BodyType::Dynamic);
+        // Above the rounded cap
+        let top = Body::new_circle(Point2::new(0.0, 3.5), 1.0, Material::wood(), BodyType::Dynamic);
+
+        let collision = check_collision(&capsule, &side, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, 0.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.5).abs() < 1e-10);
+
+        let collision = check_collision(&capsule, &top, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_capsule_capsule_collision() {
+        let body_a = Body::new_capsule(Point2::new(0.0, 0.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+        let body_b = Body::new_capsule(Point2::new(1.5, 3.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+
+        let collision = check_collision(&body_a, &body_b, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, 0.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.5).abs() < 1e-10);
+
+        let far = Body::new_capsule(Point2::new(2.5, 0.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+        assert!(check_collision(&body_a, &far, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_capsule_rectangle_collision() {
+        let ground = Body::new_rectangle(Point2::new(0.0, 0.0), 10.0, 2.0, Material::stone(), BodyType::Static);
+        // Capsule standing on the ground with its bottom cap sunk 0.25 into it
+        let capsule = Body::new_capsule(Point2::new(3.0, 3.75), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+
+        let collision = check_collision(&ground, &capsule, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.25).abs() < 1e-10);
+
+        // Capsule core pushed into the box edge from the side
+        let capsule = Body::new_capsule(Point2::new(5.5, 0.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&ground, &capsule, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, 0.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_segment_collisions() {
+        let segment = Body::new_segment(
+            Point2::new(0.0, 0.0),
+            Point2::new(-5.0, -5.0),
+            Point2::new(5.0, 5.0),
+            Material::stone(),
+        );
+        let circle = Body::new_circle(Point2::new(-1.0, 1.0), 2.0, Material::wood(), BodyType::Dynamic);
+        let rect = Body::new_rectangle(Point2::new(3.0, 2.0), 2.0, 2.0, Material::wood(), BodyType::Dynamic);
+        let capsule = Body::new_capsule(Point2::new(8.0, 0.0), 1.0, 0.5, Material::wood(), BodyType::Dynamic);
+
+        let collision = check_collision(&segment, &circle, 0, 1).unwrap();
+        let diagonal = Vector2::new(-1.0, 1.0).normalize();
+        assert!((collision.normal - diagonal).norm() < 1e-10);
+        assert!((collision.depth - (2.0 - 2.0_f64.sqrt())).abs() < 1e-10);
+
+        // The box straddles the segment: pushed out along the segment normal
+        let collision = check_collision(&segment, &rect, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, -1.0).normalize()).norm() < 1e-10);
+        assert!((collision.depth - 0.5_f64.sqrt()).abs() < 1e-10);
+
+        assert!(check_collision(&segment, &capsule, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_chain_internal_vertex_does_not_snag() {
+        // Flat floor made of short edges
+        let vertices = (0..=4).map(|i| Point2::new(i as f64 * 2.0, 0.0)).collect();
+        let floor = Body::new_chain(Point2::new(0.0, 0.0), vertices, Material::stone());
+
+        // Box sunk slightly into the floor with its corner just past the seam at x = 4
+        let rect = Body::new_rectangle(Point2::new(4.98, 0.95), 2.0, 2.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&floor, &rect, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.05).abs() < 1e-10);
+
+        // Circle resting just past the seam
+        let circle = Body::new_circle(Point2::new(4.01, 0.9), 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&floor, &circle, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_chain_convex_corner_and_end() {
+        // Plateau dropping into a cliff
+        let vertices = vec![Point2::new(-4.0, 0.0), Point2::new(0.0, 0.0), Point2::new(0.0, -4.0)];
+        let cliff = Body::new_chain(Point2::new(0.0, 0.0), vertices, Material::stone());
+
+        // Circle touching the corner diagonally keeps the rounded normal
+        let circle = Body::new_circle(Point2::new(0.5, 0.5), 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&cliff, &circle, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(1.0, 1.0).normalize()).norm() < 1e-10);
+
+        // The free end of the chain still collides around its endpoint
+        let circle = Body::new_circle(Point2::new(-4.5, 0.5), 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&cliff, &circle, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(-1.0, 1.0).normalize()).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_box_slides_across_chain_seams() {
+        let mut world = World::new();
+        // Nearly flat floor with the small height errors typical of authored terrain
+        let vertices = (0..=20).map(|i| Point2::new(i as f64 * 2.0, (i % 2) as f64 * 0.005)).collect();
+        world.add_body(Body::new_chain(Point2::new(0.0, 0.0), vertices, Material::new(2.0, 0.0, 0.0)));
+
+        let mut block = Body::new_rectangle(Point2::new(2.0, 1.0), 2.0, 2.0, Material::new(0.5, 0.0, 0.0), BodyType::Dynamic);
+        block.velocity = Vector2::new(10.0, 0.0);
+        world.add_body(block);
+
+        for _ in 0..120 {
+            world.update(1.0 / 60.0);
+        }
+
+        // Frictionless sliding over the seams must not catch on the vertices
+        assert!(world.bodies[1].velocity.x > 9.9);
+        assert!(world.bodies[1].position.x > 20.0);
+    }
+
+    #[test]
+    fn test_compound_collisions_report_children() {
+        let ground = Body::new_rectangle(Point2::new(0.0, -1.0), 20.0, 2.0, Material::stone(), BodyType::Static);
+        // Table: a top and two legs, with the legs sunk 0.1 into the ground
+        let table = Body::new_compound(
+            Point2::new(0.0, 0.0),
+            vec![
+                CompoundChild::new(Vector2::new(0.0, 2.05), Shape::Rectangle { width: 4.0, height: 0.5 }),
+                CompoundChild::new(Vector2::new(-1.75, 0.9), Shape::Rectangle { width: 0.5, height: 2.0 }),
+                CompoundChild::new(Vector2::new(1.75, 0.9), Shape::Rectangle { width: 0.5, height: 2.0 }),
+            ],
+            Material::wood(),
+            BodyType::Dynamic,
+        );
+
+        let collisions = check_collisions(&ground, &table, 0, 1);
+        assert_eq!(collisions.len(), 2);
+        let mut legs: Vec<usize> = collisions.iter().filter_map(|c| c.child_b).collect();
+        legs.sort();
+        assert_eq!(legs, vec![1, 2]);
+        for collision in &collisions {
+            assert_eq!(collision.child_a, None);
+            assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+            assert!((collision.depth - 0.1).abs() < 1e-10);
+        }
+
+        // A ball lying on the table top touches child 0, even when the ball is passed first
+        let ball = Body::new_circle(Point2::new(0.5, 2.7), 0.5, Material::rubber(), BodyType::Dynamic);
+        let collision = check_collision(&ball, &table, 2, 1).unwrap();
+        assert_eq!(collision.body_a, 1);
+        assert_eq!(collision.child_a, Some(0));
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_height_field_collisions() {
+        // Flat for two columns, then a 45 degree slope up, over a long stretch of terrain
+        let mut heights = vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0];
+        heights.extend(std::iter::repeat_n(3.0, 100_000));
+        let terrain = Body::new_height_field(Point2::new(-2.0, 0.0), heights, 1.0, Material::stone());
+
+        let rect = Body::new_rectangle(Point2::new(-0.5, 0.45), 1.0, 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&terrain, &rect, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.05).abs() < 1e-10);
+
+        let circle = Body::new_circle(Point2::new(1.0, 2.0), 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&terrain, &circle, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(-1.0, 1.0).normalize()).norm() < 1e-10);
+        assert!((collision.depth - (1.0 - 0.5_f64.sqrt())).abs() < 1e-10);
+
+        // Far along the plateau and outside the terrain entirely
+        let circle = Body::new_circle(Point2::new(50_000.3, 3.5), 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&terrain, &circle, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        let circle = Body::new_circle(Point2::new(-10.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic);
+        assert!(check_collision(&terrain, &circle, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_polygon_collisions() {
+        let triangle = |x: f64, y: f64| {
+            Body::new_polygon(
+                Point2::new(x, y),
+                vec![Point2::new(-1.0, 0.0), Point2::new(1.0, 0.0), Point2::new(0.0, 2.0)],
+                Material::wood(),
+                BodyType::Dynamic,
+            )
+            .unwrap()
+        };
+        let ground = Body::new_rectangle(Point2::new(0.0, -1.0), 10.0, 2.0, Material::stone(), BodyType::Static);
+
+        // Triangle base sunk 0.1 into the ground
+        let collision = check_collision(&ground, &triangle(0.0, -0.1), 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.1).abs() < 1e-10);
+
+        // Circle resting against the right slanted face
+        let face_normal = Vector2::new(2.0, 1.0).normalize();
+        let on_face = Point2::new(0.5, 1.0);
+        let circle = Body::new_circle(on_face + face_normal * 0.8, 1.0, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&triangle(0.0, 0.0), &circle, 0, 1).unwrap();
+        assert!((collision.normal - face_normal).norm() < 1e-10);
+        assert!((collision.depth - 0.2).abs() < 1e-10);
+
+        // Two triangles side by side, overlapping by 0.5 at the base
+        let collision = check_collision(&triangle(0.0, 0.0), &triangle(1.5, 0.0), 0, 1).unwrap();
+        assert!(collision.normal.x > 0.0);
+        assert!(check_collision(&triangle(0.0, 0.0), &triangle(2.5, 0.0), 0, 1).is_none());
+
+        // Capsule standing on the tip
+        let capsule = Body::new_capsule(Point2::new(0.0, 3.5), 1.0, 0.75, Material::wood(), BodyType::Dynamic);
+        let collision = check_collision(&triangle(0.0, 0.0), &capsule, 0, 1).unwrap();
+        assert!((collision.normal - Vector2::new(0.0, 1.0)).norm() < 1e-10);
+        assert!((collision.depth - 0.25).abs() < 1e-10);
+    }
+
     #[test]
     fn test_static_bodies_no_collision_check() {
         let mut world = World::new();
@@ -375,6 +1209,170 @@ mod tests {
         assert!((world.bodies[1].velocity - Vector2::new(1.0, 0.0)).norm() < 1e-10);
     }
 
+    #[test]
+    fn test_locked_axis_collision() {
+        let mut world = World::new();
+        world.gravity = Vector2::zeros();
+        let mut ball = Body::new_circle(Point2::new(0.0, 0.0), 1.0, Material::rubber(), BodyType::Dynamic);
+        ball.velocity = Vector2::new(5.0, -5.0);
+        world.add_body(ball);
+        // A door that can only slide vertically
+        let mut door = Body::new_rectangle(Point2::new(1.9, 0.0), 2.0, 4.0, Material::wood(), BodyType::Dynamic);
+        door.locked_axes.translation_x = true;
+        world.add_body(door);
+
+        let collisions = detect_collisions(&world.bodies);
+        resolve_collisions(&mut world, &collisions);
+
+        // The door takes no sideways velocity, so the ball bounces back as off a wall
+        let restitution = Material::rubber().combined_restitution(&Material::wood());
+        assert_eq!(world.bodies[1].velocity.x, 0.0);
+        assert!((world.bodies[0].velocity.x + 5.0 * restitution).abs() < 1e-10);
+        // Friction still drags the door along vertically
+        assert!(world.bodies[1].velocity.y < 0.0);
+    }
+
+    #[test]
+    fn test_static_and_kinetic_friction() {
+        let material = Material::new(1.0, 0.0, 0.5).with_kinetic_friction(0.2);
+        let slide = |speed: f64| {
+            let mut world = World::new();
+            world.add_body(Body::new_rectangle(Point2::new(0.0, -1.0), 10.0, 2.0, material.clone(), BodyType::Static));
+            let mut block = Body::new_rectangle(Point2::new(0.0, 0.45), 1.0, 1.0, material.clone(), BodyType::Dynamic);
+            block.velocity = Vector2::new(speed, -1.0);
+            world.add_body(block);
+
+            let collisions = detect_collisions(&world.bodies);
+            resolve_collisions(&mut world, &collisions);
+            world.bodies[1].velocity.x
+        };
+
+        // Landing at 1 m/s gives a normal impulse of one unit of mass per unit of speed:
+        // a slow block sticks, a fast one only loses the kinetic share
+        assert!(slide(0.4).abs() < 1e-10);
+        assert!((slide(2.0) - 1.8).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_restitution_threshold() {
+        let bounce = |speed: f64, threshold: f64| {
+            let mut world = World::new();
+            world.restitution_threshold = threshold;
+            world.add_body(Body::new_rectangle(Point2::new(0.0, -1.0), 10.0, 2.0, Material::rubber(), BodyType::Static));
+            let mut ball = Body::new_circle(Point2::new(0.0, 0.45), 0.5, Material::rubber(), BodyType::Dynamic);
+            ball.velocity = Vector2::new(0.0, -speed);
+            world.add_body(ball);
+
+            let collisions = detect_collisions(&world.bodies);
+            resolve_collisions(&mut world, &collisions);
+            world.bodies[1].velocity.y
+        };
+
+        assert!((bounce(5.0, 1.0) - 5.0 * 0.8).abs() < 1e-10);
+        assert!(bounce(0.5, 1.0).abs() < 1e-10);
+
+        // Just below the threshold the contact is inelastic, at or above it the ball bounces
+        assert!(bounce(0.99, 1.0).abs() < 1e-10);
+        assert!((bounce(1.0, 1.0) - 0.8).abs() < 1e-10);
+        assert!((bounce(1.01, 1.0) - 1.01 * 0.8).abs() < 1e-10);
+
+        // The default keeps restitution for every approaching contact, however slow
+        assert_eq!(World::new().restitution_threshold, 0.0);
+        assert!((bounce(0.5, 0.0) - 0.5 * 0.8).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_position_correction() {
+        let mut bodies = vec![
+            Body::new_rectangle(Point2::new(0.0, -1.0), 10.0, 2.0, Material::stone(), BodyType::Static),
+            Body::new_circle(Point2::new(0.0, 0.3), 0.5, Material::wood(), BodyType::Dynamic),
+        ];
+        let collisions = detect_collisions(&bodies);
+        correct_positions(&mut bodies, &collisions);
+
+        // Only the dynamic ball moves, leaving the slop plus a fifth of the rest
+        let depth = 0.2;
+        let expected = 0.3 + (depth - LINEAR_SLOP) * CORRECTION_PERCENT;
+        assert!((bodies[1].position.y - expected).abs() < 1e-10);
+        assert_eq!(bodies[0].position, Point2::new(0.0, -1.0));
+        assert_eq!(bodies[1].velocity, Vector2::zeros());
+    }
+
+    #[test]
+    fn test_anisotropic_friction_and_surface_velocity() {
+        // Ice track: slick when sliding along it (horizontally), grippy against its vertical sides
+        let ice = Material::new(1.0, 0.0, 1.0)
+            .with_anisotropic_friction(Vector2::new(1.0, 0.0), 0.0, 1.0)
+            .with_friction_combine(CombineRule::Min);
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, -1.0), 4.0, 2.0, ice, BodyType::Static));
+        let mut on_top = Body::new_rectangle(Point2::new(0.0, 0.45), 1.0, 1.0, Material::wood(), BodyType::Dynamic);
+        on_top.velocity = Vector2::new(3.0, -1.0);
+        world.add_body(on_top);
+        let mut against_side = Body::new_rectangle(Point2::new(2.45, -1.0), 1.0, 1.0, Material::wood(), BodyType::Dynamic);
+        against_side.velocity = Vector2::new(-1.0, 3.0);
+        world.add_body(against_side);
+
+        let collisions = detect_collisions(&world.bodies);
+        resolve_collisions(&mut world, &collisions);
+        assert_eq!(world.bodies[1].velocity.x, 3.0);
+        assert!(world.bodies[2].velocity.y < 3.0);
+
+        // Conveyor belt: a box resting on it is dragged to the belt speed
+        let mut world = World::new();
+        let mut belt = Body::new_rectangle(Point2::new(0.0, -1.0), 20.0, 2.0, Material::stone(), BodyType::Static);
+        belt.surface_velocity = Vector2::new(2.0, 0.0);
+        world.add_body(belt);
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.5), 1.0, 1.0, Material::wood(), BodyType::Dynamic));
+        for _ in 0..120 {
+            world.update(1.0 / 60.0);
+        }
+        assert!((world.bodies[1].velocity.x - 2.0).abs() < 1e-6);
+        assert!(world.bodies[1].position.x > 2.0);
+    }
+
+    #[test]
+    fn test_one_way_passes() {
+        let mut platform = Body::new_rectangle(Point2::new(0.0, 0.0), 4.0, 0.2, Material::wood(), BodyType::Static);
+        platform.one_way = Some(Vector2::new(0.0, 1.0));
+        let from_below = Body::new_circle(Point2::new(-1.0, -0.5), 0.45, Material::wood(), BodyType::Dynamic);
+        let from_above = Body::new_circle(Point2::new(1.0, 0.5), 0.45, Material::wood(), BodyType::Dynamic);
+        let mut bodies = vec![platform, from_below, from_above];
+
+        let mut contacts = HashMap::new();
+        let collisions = detect_collisions(&bodies);
+        assert_eq!(collisions.len(), 2);
+        update_one_way_contacts(&bodies, &collisions, &mut contacts);
+        assert_eq!(contacts, HashMap::from([((0, 1), true), ((0, 2), false)]));
+
+        // Halfway through, the contact normal points up but the pass continues
+        bodies[1].position.y = 0.3;
+        let mut collisions = detect_collisions(&bodies);
+        assert!(collisions.iter().any(|collision| collision.body_b == 1 && collision.normal.y > 0.0));
+        update_one_way_contacts(&bodies, &collisions, &mut contacts);
+        remove_one_way_passes(&mut collisions, &contacts);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].body_b, 2);
+
+        // Once clear of the platform it blocks again
+        bodies[1].position.y = 1.0;
+        update_one_way_contacts(&bodies, &detect_collisions(&bodies), &mut contacts);
+        assert_eq!(contacts, HashMap::from([((0, 2), false)]));
+
+        // Jumping up deep into the platform in one step still passes, while a body bouncing
+        // off the top keeps being blocked for as long as it touches
+        bodies[1].position.y = 0.3;
+        bodies[1].velocity = Vector2::new(0.0, 5.0);
+        bodies[2].velocity = Vector2::new(0.0, 1.0);
+        update_one_way_contacts(&bodies, &detect_collisions(&bodies), &mut contacts);
+        assert_eq!(contacts, HashMap::from([((0, 1), true), ((0, 2), false)]));
+
+        // Contacts are dropped when their body stops being one-way
+        bodies[0].one_way = None;
+        update_one_way_contacts(&bodies, &detect_collisions(&bodies), &mut contacts);
+        assert!(contacts.is_empty());
+    }
+
     #[test]
     fn test_static_dynamic_collision() {
         let mut world = World::new();
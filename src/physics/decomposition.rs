@@ -0,0 +1,298 @@
+//! Splitting simple polygons into convex pieces
+
+use std::fmt;
+use nalgebra::{Point2, Vector2};
+
+const EPSILON: f64 = 1e-9;
+
+/// Reasons a polygon outline cannot be turned into convex pieces
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecompositionError {
+    /// Fewer than three vertices were given
+    TooFewVertices,
+    /// Repeated vertices, or an outline enclosing no area
+    Degenerate,
+    /// Two edges of the outline cross or touch
+    SelfIntersecting,
+    /// A convex polygon was required but the outline is concave
+    NotConvex,
+}
+
+impl fmt::Display for DecompositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompositionError::TooFewVertices => write!(f, "a polygon needs at least three vertices"),
+            DecompositionError::Degenerate => write!(f, "polygon has repeated vertices or no area"),
+            DecompositionError::SelfIntersecting => write!(f, "polygon edges intersect each other"),
+            DecompositionError::NotConvex => write!(f, "polygon is not convex"),
+        }
+    }
+}
+
+impl std::error::Error for DecompositionError {}
+
+/// Splits a simple polygon (either winding) into convex pieces with counter-clockwise vertices.
+///
+/// The outline is triangulated by ear clipping, then triangles are merged back together
+/// wherever the result stays convex (Hertel-Mehlhorn).
+pub fn decompose_polygon(vertices: &[Point2<f64>]) -> Result<Vec<Vec<Point2<f64>>>, DecompositionError> {
+    let outline = prepare_outline(vertices)?;
+    if is_convex(&outline) {
+        return Ok(vec![outline]);
+    }
+
+    let triangles = triangulate(&outline)?;
+    let pieces = merge_convex(&outline, triangles);
+    Ok(pieces
+        .into_iter()
+        .map(|piece| piece.into_iter().map(|i| outline[i]).collect())
+        .collect())
+}
+
+/// Validates a convex outline and returns it in counter-clockwise order
+pub(crate) fn convex_counter_clockwise(vertices: Vec<Point2<f64>>) -> Result<Vec<Point2<f64>>, DecompositionError> {
+    let outline = prepare_outline(&vertices)?;
+    if !is_convex(&outline) {
+        return Err(DecompositionError::NotConvex);
+    }
+    Ok(outline)
+}
+
+fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+fn signed_area(vertices: &[Point2<f64>]) -> f64 {
+    (0..vertices.len())
+        .map(|i| cross(vertices[i].coords, vertices[(i + 1) % vertices.len()].coords))
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Checks the outline, drops collinear vertices and orients it counter-clockwise
+fn prepare_outline(vertices: &[Point2<f64>]) -> Result<Vec<Point2<f64>>, DecompositionError> {
+    if vertices.len() < 3 {
+        return Err(DecompositionError::TooFewVertices);
+    }
+    let n = vertices.len();
+    if (0..n).any(|i| (vertices[(i + 1) % n] - vertices[i]).norm() < EPSILON) {
+        return Err(DecompositionError::Degenerate);
+    }
+
+    // Collinear vertices add nothing and would only produce zero-area ears
+    let mut outline: Vec<Point2<f64>> = vertices.to_vec();
+    let mut i = 0;
+    while i < outline.len() && outline.len() >= 3 {
+        let m = outline.len();
+        let (prev, current, next) = (outline[(i + m - 1) % m], outline[i], outline[(i + 1) % m]);
+        if cross(current - prev, next - current).abs() < EPSILON && (current - prev).dot(&(next - current)) > 0.0 {
+            outline.remove(i);
+            i = 0;
+        } else {
+            i += 1;
+        }
+    }
+    if outline.len() < 3 {
+        return Err(DecompositionError::Degenerate);
+    }
+    if is_self_intersecting(&outline) {
+        return Err(DecompositionError::SelfIntersecting);
+    }
+    if signed_area(&outline).abs() < EPSILON {
+        return Err(DecompositionError::Degenerate);
+    }
+
+    if signed_area(&outline) < 0.0 {
+        outline.reverse();
+    }
+    Ok(outline)
+}
+
+/// Whether segments `p1-p2` and `q1-q2` cross or touch
+fn segments_intersect(p1: Point2<f64>, p2: Point2<f64>, q1: Point2<f64>, q2: Point2<f64>) -> bool {
+    let d1 = cross(p2 - p1, q1 - p1);
+    let d2 = cross(p2 - p1, q2 - p1);
+    let d3 = cross(q2 - q1, p1 - q1);
+    let d4 = cross(q2 - q1, p2 - q1);
+
+    if ((d1 > EPSILON && d2 < -EPSILON) || (d1 < -EPSILON && d2 > EPSILON))
+        && ((d3 > EPSILON && d4 < -EPSILON) || (d3 < -EPSILON && d4 > EPSILON))
+    {
+        return true;
+    }
+
+    // Touching or collinear overlap
+    let on_segment = |a: Point2<f64>, b: Point2<f64>, p: Point2<f64>, d: f64| {
+        d.abs() <= EPSILON
+            && p.x >= a.x.min(b.x) - EPSILON
+            && p.x <= a.x.max(b.x) + EPSILON
+            && p.y >= a.y.min(b.y) - EPSILON
+            && p.y <= a.y.max(b.y) + EPSILON
+    };
+    on_segment(p1, p2, q1, d1) || on_segment(p1, p2, q2, d2) || on_segment(q1, q2, p1, d3) || on_segment(q1, q2, p2, d4)
+}
+
+fn is_self_intersecting(outline: &[Point2<f64>]) -> bool {
+    let n = outline.len();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            // Adjacent edges share a vertex by construction
+            if j == i + 1 || (i == 0 && j == n - 1) {
+                continue;
+            }
+            if segments_intersect(outline[i], outline[(i + 1) % n], outline[j], outline[(j + 1) % n]) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Whether every turn of a counter-clockwise outline is a left turn (straight is allowed)
+fn is_convex(outline: &[Point2<f64>]) -> bool {
+    let n = outline.len();
+    (0..n).all(|i| {
+        let (prev, current, next) = (outline[(i + n - 1) % n], outline[i], outline[(i + 1) % n]);
+        cross(current - prev, next - current) >= -EPSILON
+    })
+}
+
+fn point_in_triangle(p: Point2<f64>, a: Point2<f64>, b: Point2<f64>, c: Point2<f64>) -> bool {
+    cross(b - a, p - a) >= -EPSILON && cross(c - b, p - b) >= -EPSILON && cross(a - c, p - c) >= -EPSILON
+}
+
+/// Ear clipping triangulation of a counter-clockwise outline, as vertex indices
+fn triangulate(outline: &[Point2<f64>]) -> Result<Vec<Vec<usize>>, DecompositionError> {
+    let mut remaining: Vec<usize> = (0..outline.len()).collect();
+    let mut triangles = Vec::with_capacity(outline.len() - 2);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&k| {
+            let (prev, current, next) = (remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]);
+            let (a, b, c) = (outline[prev], outline[current], outline[next]);
+            if cross(b - a, c - b) <= EPSILON {
+                return false;
+            }
+            remaining
+                .iter()
+                .filter(|&&j| j != prev && j != current && j != next)
+                .all(|&j| !point_in_triangle(outline[j], a, b, c))
+        });
+
+        // A simple polygon always has an ear; not finding one means the input was invalid
+        let k = ear.ok_or(DecompositionError::Degenerate)?;
+        triangles.push(vec![remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]]);
+        remaining.remove(k);
+    }
+    triangles.push(remaining);
+    Ok(triangles)
+}
+
+/// Hertel-Mehlhorn: removes diagonals between pieces whenever the merged piece stays convex
+fn merge_convex(outline: &[Point2<f64>], mut pieces: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    'merging: loop {
+        for a in 0..pieces.len() {
+            for b in (a + 1)..pieces.len() {
+                if let Some(merged) = merge_along_shared_edge(&pieces[a], &pieces[b]) {
+                    let points: Vec<Point2<f64>> = merged.iter().map(|&i| outline[i]).collect();
+                    if is_convex(&points) {
+                        pieces[a] = merged;
+                        pieces.remove(b);
+                        continue 'merging;
+                    }
+                }
+            }
+        }
+        return pieces;
+    }
+}
+
+/// Joins two counter-clockwise pieces sharing an edge (traversed in opposite directions)
+fn merge_along_shared_edge(p: &[usize], q: &[usize]) -> Option<Vec<usize>> {
+    for i in 0..p.len() {
+        let (u, v) = (p[i], p[(i + 1) % p.len()]);
+        if let Some(j) = (0..q.len()).find(|&j| q[j] == v && q[(j + 1) % q.len()] == u) {
+            // Walk p from v round to u, then q from just after u round to just before v
+            let mut merged: Vec<usize> = (1..=p.len()).map(|k| p[(i + k) % p.len()]).collect();
+            merged.extend((2..q.len()).map(|k| q[(j + k) % q.len()]));
+            return Some(merged);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point2<f64>> {
+        coords.iter().map(|&(x, y)| Point2::new(x, y)).collect()
+    }
+
+    fn total_area(pieces: &[Vec<Point2<f64>>]) -> f64 {
+        pieces.iter().map(|piece| signed_area(piece)).sum()
+    }
+
+    #[test]
+    fn test_convex_input_is_single_piece() {
+        // Clockwise square with a redundant midpoint
+        let square = points(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 1.0), (2.0, 0.0)]);
+        let pieces = decompose_polygon(&square).unwrap();
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(pieces[0].len(), 4);
+        assert!((signed_area(&pieces[0]) - 4.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_l_shape_decomposition() {
+        let l_shape = points(&[(0.0, 0.0), (3.0, 0.0), (3.0, 1.0), (1.0, 1.0), (1.0, 3.0), (0.0, 3.0)]);
+        let pieces = decompose_polygon(&l_shape).unwrap();
+
+        assert_eq!(pieces.len(), 2);
+        assert!((total_area(&pieces) - 5.0).abs() < 1e-10);
+        for piece in &pieces {
+            assert!(is_convex(piece));
+            assert!(signed_area(piece) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_u_shape_decomposition() {
+        let u_shape = points(&[
+            (0.0, 0.0), (5.0, 0.0), (5.0, 4.0), (4.0, 4.0),
+            (4.0, 1.0), (1.0, 1.0), (1.0, 4.0), (0.0, 4.0),
+        ]);
+        let pieces = decompose_polygon(&u_shape).unwrap();
+
+        // Hertel-Mehlhorn is within a factor of four of the optimum of three pieces
+        assert!(pieces.len() >= 3 && pieces.len() <= 6);
+        assert!((total_area(&pieces) - 11.0).abs() < 1e-10);
+        assert!(pieces.iter().all(|piece| is_convex(piece)));
+    }
+
+    #[test]
+    fn test_invalid_outlines_are_rejected() {
+        assert_eq!(
+            decompose_polygon(&points(&[(0.0, 0.0), (1.0, 0.0)])),
+            Err(DecompositionError::TooFewVertices)
+        );
+        assert_eq!(
+            decompose_polygon(&points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)])),
+            Err(DecompositionError::Degenerate)
+        );
+        assert_eq!(
+            decompose_polygon(&points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (0.0, 1.0)])),
+            Err(DecompositionError::Degenerate)
+        );
+        // Bow tie
+        assert_eq!(
+            decompose_polygon(&points(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)])),
+            Err(DecompositionError::SelfIntersecting)
+        );
+        assert_eq!(
+            convex_counter_clockwise(points(&[(0.0, 0.0), (2.0, 0.0), (1.0, 0.5), (1.0, 2.0)])),
+            Err(DecompositionError::NotConvex)
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Force fields acting on bodies in addition to world gravity
+
+use nalgebra::{Point2, Vector2};
+use crate::physics::bodies::Body;
+use crate::physics::collisions::Aabb;
+
+/// A force applied to every dynamic body it affects, once per step before integration
+pub trait ForceField {
+    /// Force on `body` at simulation time `time` (seconds since the world was created)
+    fn force(&self, body: &Body, time: f64) -> Vector2<f64>;
+
+    /// Field categories this field acts on, matched against [`Body::field_category`]
+    fn mask(&self) -> u32 {
+        u32::MAX
+    }
+}
+
+/// Distance below which inverse square fields stop growing, avoiding a singularity at the center
+const INVERSE_SQUARE_SOFTENING: f64 = 0.1;
+
+/// How the strength of a radial field changes with distance from its center
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Falloff {
+    /// Full strength everywhere within the radius
+    Constant,
+    /// Full strength at the center, fading linearly to zero at the radius
+    Linear,
+    /// Strength divided by the squared distance, like gravity
+    #[default]
+    InverseSquare,
+}
+
+/// Pulls bodies towards a point (positive strength) or pushes them away (negative strength).
+/// The strength is an acceleration, so light and heavy bodies move alike.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointField {
+    pub center: Point2<f64>,
+    /// Acceleration at the center, or at distance 1 for [`Falloff::InverseSquare`]
+    pub strength: f64,
+    /// Bodies farther away are unaffected
+    pub radius: f64,
+    pub falloff: Falloff,
+    pub mask: u32,
+}
+
+impl PointField {
+    /// Creates an unbounded inverse square attractor (or repulsor for negative strength)
+    pub fn new(center: Point2<f64>, strength: f64) -> Self {
+        Self {
+            center,
+            strength,
+            radius: f64::INFINITY,
+            falloff: Falloff::InverseSquare,
+            mask: u32::MAX,
+        }
+    }
+
+    /// Limits the field to bodies within `radius` of the center
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets how the strength changes with distance
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Limits the field to bodies in the given field categories
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+}
+
+impl ForceField for PointField {
+    fn force(&self, body: &Body, _time: f64) -> Vector2<f64> {
+        let offset = self.center - body.center_of_mass();
+        let distance = offset.norm();
+        if distance >= self.radius || distance < 1e-10 {
+            return Vector2::zeros();
+        }
+        let acceleration = match self.falloff {
+            Falloff::Constant => self.strength,
+            Falloff::Linear => self.strength * (1.0 - distance / self.radius),
+            Falloff::InverseSquare => self.strength / distance.max(INVERSE_SQUARE_SOFTENING).powi(2),
+        };
+        offset / distance * acceleration * body.mass
+    }
+
+    fn mask(&self) -> u32 {
+        self.mask
+    }
+}
+
+/// Wind blowing at a constant velocity, optionally only inside a region.
+/// Bodies are dragged towards the wind velocity, so they stop speeding up once they move with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wind {
+    pub velocity: Vector2<f64>,
+    /// Force per unit of velocity difference (N*s/m)
+    pub drag: f64,
+    /// Bodies whose position lies outside the region are unaffected
+    pub region: Option<Aabb>,
+    pub mask: u32,
+}
+
+impl Wind {
+    /// Creates wind blowing everywhere
+    pub fn new(velocity: Vector2<f64>, drag: f64) -> Self {
+        Self {
+            velocity,
+            drag,
+            region: None,
+            mask: u32::MAX,
+        }
+    }
+
+    /// Limits the wind to bodies inside `region`
+    pub fn with_region(mut self, region: Aabb) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Limits the wind to bodies in the given field categories
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+}
+
+impl ForceField for Wind {
+    fn force(&self, body: &Body, _time: f64) -> Vector2<f64> {
+        if self.region.is_some_and(|region| !region.contains(body.center_of_mass())) {
+            return Vector2::zeros();
+        }
+        (self.velocity - body.velocity) * self.drag
+    }
+
+    fn mask(&self) -> u32 {
+        self.mask
+    }
+}
+
+/// Swirls bodies around a point: counter-clockwise for positive strength, clockwise for negative
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vortex {
+    pub center: Point2<f64>,
+    /// Tangential acceleration at the center, fading linearly to zero at the radius
+    pub strength: f64,
+    pub radius: f64,
+    /// Additional acceleration towards the center, fading the same way
+    pub inward: f64,
+    pub mask: u32,
+}
+
+impl Vortex {
+    /// Creates a vortex that only swirls, without pulling inwards
+    pub fn new(center: Point2<f64>, strength: f64, radius: f64) -> Self {
+        Self {
+            center,
+            strength,
+            radius,
+            inward: 0.0,
+            mask: u32::MAX,
+        }
+    }
+
+    /// Adds a pull towards the center, like a whirlpool
+    pub fn with_inward(mut self, inward: f64) -> Self {
+        self.inward = inward;
+        self
+    }
+
+    /// Limits the vortex to bodies in the given field categories
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+}
+
+impl ForceField for Vortex {
+    fn force(&self, body: &Body, _time: f64) -> Vector2<f64> {
+        let offset = body.center_of_mass() - self.center;
+        let distance = offset.norm();
+        if distance >= self.radius || distance < 1e-10 {
+            return Vector2::zeros();
+        }
+        let radial = offset / distance;
+        let tangent = Vector2::new(-radial.y, radial.x);
+        let fade = 1.0 - distance / self.radius;
+        (tangent * self.strength - radial * self.inward) * fade * body.mass
+    }
+
+    fn mask(&self) -> u32 {
+        self.mask
+    }
+}
+
+/// Smoothly varying pseudo-random gusts, optionally only inside a region.
+/// The same seed, position and time always give the same force.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turbulence {
+    /// Largest acceleration of a gust
+    pub strength: f64,
+    /// Typical size of a gust (m)
+    pub scale: f64,
+    /// How many times per second the gusts change
+    pub frequency: f64,
+    pub region: Option<Aabb>,
+    pub seed: u32,
+    pub mask: u32,
+}
+
+impl Turbulence {
+    /// Creates turbulence everywhere, changing once per second
+    pub fn new(strength: f64, scale: f64) -> Self {
+        Self {
+            strength,
+            scale,
+            frequency: 1.0,
+            region: None,
+            seed: 0,
+            mask: u32::MAX,
+        }
+    }
+
+    /// Sets how many times per second the gusts change
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Limits the turbulence to bodies inside `region`
+    pub fn with_region(mut self, region: Aabb) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Picks a different gust pattern
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Limits the turbulence to bodies in the given field categories
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+}
+
+impl ForceField for Turbulence {
+    fn force(&self, body: &Body, time: f64) -> Vector2<f64> {
+        let center = body.center_of_mass();
+        if self.region.is_some_and(|region| !region.contains(center)) {
+            return Vector2::zeros();
+        }
+        let sample = [center.x / self.scale, center.y / self.scale, time * self.frequency];
+        let gust = Vector2::new(value_noise(sample, self.seed), value_noise(sample, self.seed ^ 0x9e37_79b9));
+        gust * self.strength * body.mass
+    }
+
+    fn mask(&self) -> u32 {
+        self.mask
+    }
+}
+
+/// Smooth noise in [-1, 1]: random values on an integer lattice, blended with smoothstep
+fn value_noise(point: [f64; 3], seed: u32) -> f64 {
+    let cell = point.map(f64::floor);
+    let t = [0, 1, 2].map(|axis| {
+        let f = point[axis] - cell[axis];
+        f * f * (3.0 - 2.0 * f)
+    });
+    let mut value = 0.0;
+    for corner in 0..8 {
+        let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+        let weight: f64 = (0..3).map(|axis| if offset[axis] == 1 { t[axis] } else { 1.0 - t[axis] }).product();
+        let lattice = [0, 1, 2].map(|axis| cell[axis] as i64 + offset[axis] as i64);
+        value += weight * lattice_value(lattice, seed);
+    }
+    value
+}
+
+/// Random value in [-1, 1] for a lattice point
+fn lattice_value(lattice: [i64; 3], seed: u32) -> f64 {
+    let mut hash = seed as u64 ^ 0xcbf2_9ce4_8422_2325;
+    for coordinate in lattice {
+        hash = (hash ^ coordinate as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        hash ^= hash >> 29;
+    }
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 32;
+    (hash >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::World;
+    use crate::physics::bodies::{BodyType, MassProperties, Material};
+
+    fn ball_at(x: f64, y: f64) -> Body {
+        Body::new_circle(Point2::new(x, y), 0.5, Material::wood(), BodyType::Dynamic)
+    }
+
+    #[test]
+    fn test_point_field_falloff() {
+        let body = ball_at(2.0, 0.0);
+        let attractor = PointField::new(Point2::origin(), 8.0);
+        assert!((attractor.force(&body, 0.0) - Vector2::new(-2.0, 0.0) * body.mass).norm() < 1e-10);
+
+        let repulsor = PointField::new(Point2::origin(), -3.0)
+            .with_radius(4.0)
+            .with_falloff(Falloff::Linear);
+        assert!((repulsor.force(&body, 0.0) - Vector2::new(1.5, 0.0) * body.mass).norm() < 1e-10);
+        assert_eq!(repulsor.clone().with_radius(2.0).force(&body, 0.0), Vector2::zeros());
+
+        let constant = PointField::new(Point2::new(2.0, 3.0), 1.0).with_falloff(Falloff::Constant);
+        assert!((constant.force(&body, 0.0) - Vector2::new(0.0, 1.0) * body.mass).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_wind_and_vortex() {
+        let mut body = ball_at(1.0, 0.0);
+        body.velocity = Vector2::new(1.0, 0.0);
+        let region = Aabb { min: Point2::new(0.0, -1.0), max: Point2::new(5.0, 1.0) };
+        let wind = Wind::new(Vector2::new(4.0, 0.0), 0.5).with_region(region);
+        assert_eq!(wind.force(&body, 0.0), Vector2::new(1.5, 0.0));
+        assert_eq!(wind.force(&ball_at(-1.0, 0.0), 0.0), Vector2::zeros());
+
+        let vortex = Vortex::new(Point2::origin(), 2.0, 4.0).with_inward(1.0);
+        let force = vortex.force(&body, 0.0) / body.mass;
+        assert!((force - Vector2::new(-0.75, 1.5)).norm() < 1e-10);
+        assert_eq!(vortex.force(&ball_at(5.0, 0.0), 0.0), Vector2::zeros());
+    }
+
+    #[test]
+    fn test_fields_sample_the_center_of_mass() {
+        let mut body = ball_at(0.0, 0.0);
+        body.set_mass_properties(MassProperties { mass: 2.0, center_of_mass: Vector2::new(2.0, 0.0), inertia: 1.0 });
+
+        let attractor = PointField::new(Point2::new(4.0, 0.0), 1.0).with_radius(3.0).with_falloff(Falloff::Constant);
+        assert_eq!(attractor.force(&body, 0.0), Vector2::new(2.0, 0.0));
+
+        let region = Aabb { min: Point2::new(1.0, -1.0), max: Point2::new(3.0, 1.0) };
+        let wind = Wind::new(Vector2::new(1.0, 0.0), 1.0).with_region(region);
+        assert_eq!(wind.force(&body, 0.0), Vector2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_turbulence_is_smooth_and_bounded() {
+        let turbulence = Turbulence::new(3.0, 2.0).with_frequency(0.5);
+        let body = ball_at(0.3, -1.7);
+        let force = turbulence.force(&body, 1.25);
+        assert_eq!(force, turbulence.force(&body, 1.25));
+        assert_ne!(force, turbulence.clone().with_seed(7).force(&body, 1.25));
+
+        let mut previous = force;
+        let mut changed = false;
+        for step in 1..=200 {
+            let next = turbulence.force(&body, 1.25 + step as f64 * 0.01);
+            assert!(next.x.abs() <= 3.0 * body.mass && next.y.abs() <= 3.0 * body.mass);
+            assert!((next - previous).norm() < 0.1 * body.mass);
+            changed |= next != force;
+            previous = next;
+        }
+        assert!(changed);
+    }
+
+    #[test]
+    fn test_fields_in_world_filtered_by_category() {
+        let mut world = World::new();
+        world.gravity = Vector2::zeros();
+        world.add_body(ball_at(4.0, 0.0));
+        let mut ghost = ball_at(-4.0, 0.0);
+        ghost.field_category = 0b10;
+        world.add_body(ghost);
+        world.add_force_field(PointField::new(Point2::origin(), 5.0).with_falloff(Falloff::Constant).with_mask(0b01));
+
+        for _ in 0..30 {
+            world.update(1.0 / 60.0);
+        }
+        assert!(world.bodies[0].position.x < 3.5);
+        assert_eq!(world.bodies[1].position.x, -4.0);
+        assert!((world.time - 0.5).abs() < 1e-9);
+    }
+}
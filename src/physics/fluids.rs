@@ -0,0 +1,379 @@
+//! Fluid volumes applying buoyancy and drag to the bodies inside them
+
+use std::fmt;
+use nalgebra::{Point2, Vector2};
+use crate::physics::bodies::{Body, Shape};
+use crate::physics::decomposition::{self, DecompositionError};
+use crate::physics::gjk::{self, ConvexCore};
+
+/// Sides of the polygons standing in for round shapes when clipping them against a fluid
+const ROUND_SEGMENTS: usize = 32;
+
+/// A convex region of fluid such as a pool of water. Bodies overlapping it are pushed up by the
+/// weight of the fluid they displace and slowed down relative to the flow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluidVolume {
+    /// Counter-clockwise convex outline of the fluid in world space
+    pub region: Vec<Point2<f64>>,
+    /// Density of the fluid (kg/m^2), compared against body material densities
+    pub density: f64,
+    /// Drag force per unit of submerged area and relative speed
+    pub linear_drag: f64,
+    /// Drag force per unit of submerged area and squared relative speed
+    pub quadratic_drag: f64,
+    /// Velocity the fluid moves at, like a river current
+    pub flow_velocity: Vector2<f64>,
+}
+
+/// Reasons a fluid volume cannot be created
+#[derive(Debug, Clone, PartialEq)]
+pub enum FluidError {
+    /// The outline is not a valid convex polygon
+    Region(DecompositionError),
+    /// A rectangle's width and height must be positive and finite
+    InvalidSize,
+    /// The density must be finite and not negative
+    InvalidDensity,
+}
+
+impl fmt::Display for FluidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FluidError::Region(error) => write!(f, "invalid fluid region: {}", error),
+            FluidError::InvalidSize => write!(f, "fluid width and height must be positive"),
+            FluidError::InvalidDensity => write!(f, "fluid density must be finite and not negative"),
+        }
+    }
+}
+
+impl std::error::Error for FluidError {}
+
+impl From<DecompositionError> for FluidError {
+    fn from(error: DecompositionError) -> Self {
+        FluidError::Region(error)
+    }
+}
+
+/// The part of a shape that lies inside a fluid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Submerged {
+    pub area: f64,
+    /// Center of the submerged area, where buoyancy and drag act
+    pub centroid: Point2<f64>,
+}
+
+impl FluidVolume {
+    /// Creates a still, drag-free axis-aligned box of fluid
+    pub fn rectangle(center: Point2<f64>, width: f64, height: f64, density: f64) -> Result<Self, FluidError> {
+        // Written so that NaN fails too
+        if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+            return Err(FluidError::InvalidSize);
+        }
+        let (hw, hh) = (width / 2.0, height / 2.0);
+        Self::from_region(
+            vec![
+                center + Vector2::new(-hw, -hh),
+                center + Vector2::new(hw, -hh),
+                center + Vector2::new(hw, hh),
+                center + Vector2::new(-hw, hh),
+            ],
+            density,
+        )
+    }
+
+    /// Creates a still, drag-free fluid with a convex outline given in either winding
+    pub fn polygon(vertices: &[Point2<f64>], density: f64) -> Result<Self, FluidError> {
+        let region = decomposition::convex_counter_clockwise(vertices.to_vec())?;
+        Self::from_region(region, density)
+    }
+
+    fn from_region(region: Vec<Point2<f64>>, density: f64) -> Result<Self, FluidError> {
+        if !(density >= 0.0 && density.is_finite()) {
+            return Err(FluidError::InvalidDensity);
+        }
+        Ok(Self {
+            region,
+            density,
+            linear_drag: 0.0,
+            quadratic_drag: 0.0,
+            flow_velocity: Vector2::zeros(),
+        })
+    }
+
+    /// Sets the linear and quadratic drag coefficients
+    pub fn with_drag(mut self, linear_drag: f64, quadratic_drag: f64) -> Self {
+        self.linear_drag = linear_drag;
+        self.quadratic_drag = quadratic_drag;
+        self
+    }
+
+    /// Sets the velocity the fluid flows at
+    pub fn with_flow(mut self, flow_velocity: Vector2<f64>) -> Self {
+        self.flow_velocity = flow_velocity;
+        self
+    }
+
+    /// Area and centroid of the part of a shape placed at `position` that lies inside the fluid,
+    /// or `None` if it does not reach into it. A circle crossing a single edge of the fluid, such as
+    /// its surface, is cut exactly; other round shapes are approximated by polygons of the same area.
+    pub fn submerged(&self, shape: &Shape, position: Point2<f64>) -> Option<Submerged> {
+        let circle_segment = match shape {
+            Shape::Circle { radius } => circle_inside_region(position, *radius, &self.region),
+            _ => None,
+        };
+        let (area, centroid) = circle_segment.unwrap_or_else(|| {
+            let (area, moment) = shape_polygons(shape, position)
+                .iter()
+                .map(|polygon| area_and_centroid(&clip_polygon(polygon, &self.region)))
+                .fold((0.0, Vector2::zeros()), |(area, moment), (piece_area, centroid)| {
+                    (area + piece_area, moment + centroid.coords * piece_area)
+                });
+            (area, Point2::from(moment / area))
+        });
+        (area > 1e-12).then_some(Submerged { area, centroid })
+    }
+
+    /// Buoyancy and drag force on `body` under `gravity`, and their torque about its center of mass.
+    /// Buoyancy comes from the weight of the displaced fluid, so it is scaled by the body's
+    /// `gravity_scale` like the body's own weight: a body floats at the same depth whatever its
+    /// scale, and one that ignores gravity is not lifted either.
+    pub fn force_and_torque(&self, body: &Body, gravity: Vector2<f64>) -> (Vector2<f64>, f64) {
+        let Some(Submerged { area, centroid }) = self.submerged(&body.shape, body.position) else {
+            return (Vector2::zeros(), 0.0);
+        };
+        let buoyancy = -gravity * body.gravity_scale * self.density * area;
+
+        // Drag against the velocity relative to the flow, acting at the submerged centroid
+        let relative_velocity = body.velocity - self.flow_velocity;
+        let drag = -relative_velocity * (self.linear_drag + self.quadratic_drag * relative_velocity.norm()) * area;
+
+        // Spin is damped by the linear coefficient times the polar moment of a disc of the submerged area
+        let polar_moment = area * area / (2.0 * std::f64::consts::PI);
+        let spin_drag = -body.angular_velocity * self.linear_drag * polar_moment;
+
+        let force = buoyancy + drag;
+        let arm = centroid - body.center_of_mass();
+        (force, arm.x * force.y - arm.y * force.x + spin_drag)
+    }
+}
+
+/// Area and centroid of the part of a circle inside the counter-clockwise convex `region`, worked
+/// out exactly when the circle crosses at most one edge line; `None` when it has to be clipped
+fn circle_inside_region(center: Point2<f64>, radius: f64, region: &[Point2<f64>]) -> Option<(f64, Point2<f64>)> {
+    let mut crossed = None;
+    for (i, &edge_start) in region.iter().enumerate() {
+        let edge = (region[(i + 1) % region.len()] - edge_start).normalize();
+        // Depth of the center inside the edge, positive on the inner (left) side
+        let depth = edge.x * (center.y - edge_start.y) - edge.y * (center.x - edge_start.x);
+        if depth <= -radius {
+            return Some((0.0, center));
+        }
+        if depth < radius {
+            if crossed.is_some() {
+                return None;
+            }
+            crossed = Some((depth, Vector2::new(edge.y, -edge.x)));
+        }
+    }
+    let full_area = std::f64::consts::PI * radius * radius;
+    let Some((depth, outward)) = crossed else {
+        return Some((full_area, center));
+    };
+
+    // Cut off the circular segment sticking out past the edge. Its centroid lies
+    // 2/3 (r^2 - d^2)^(3/2) / area beyond the center, so the rest balances it on the other side.
+    let half_chord = (radius * radius - depth * depth).sqrt();
+    let cap_area = radius * radius * (depth / radius).acos() - depth * half_chord;
+    let cap_moment = 2.0 / 3.0 * half_chord.powi(3);
+    let area = full_area - cap_area;
+    Some((area, center - outward * (cap_moment / area)))
+}
+
+/// Convex polygons covering a shape, with round shapes approximated by polygons of equal area
+fn shape_polygons(shape: &Shape, position: Point2<f64>) -> Vec<Vec<Point2<f64>>> {
+    if let Shape::Compound { children } = shape {
+        return children
+            .iter()
+            .flat_map(|child| shape_polygons(&child.shape, position + child.offset))
+            .collect();
+    }
+    let Some(core) = ConvexCore::from_shape(shape, position) else {
+        return Vec::new();
+    };
+    if core.radius <= 0.0 {
+        return vec![core.points];
+    }
+
+    // A regular polygon with this circumradius has the area of the circle
+    let step = 2.0 * std::f64::consts::PI / ROUND_SEGMENTS as f64;
+    let radius = core.radius * (step / step.sin()).sqrt();
+    let points = core
+        .points
+        .iter()
+        .flat_map(|center| {
+            (0..ROUND_SEGMENTS).map(move |i| {
+                let angle = i as f64 * step;
+                center.coords + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+        })
+        .collect();
+    vec![gjk::convex_hull(points).into_iter().map(Point2::from).collect()]
+}
+
+/// Part of `polygon` inside the counter-clockwise convex `region` (Sutherland-Hodgman)
+fn clip_polygon(polygon: &[Point2<f64>], region: &[Point2<f64>]) -> Vec<Point2<f64>> {
+    let mut clipped = polygon.to_vec();
+    for (i, &edge_start) in region.iter().enumerate() {
+        let edge = region[(i + 1) % region.len()] - edge_start;
+        // Positive on the inner (left) side of the edge
+        let side = |point: Point2<f64>| edge.x * (point.y - edge_start.y) - edge.y * (point.x - edge_start.x);
+        let input = std::mem::take(&mut clipped);
+        for (j, &current) in input.iter().enumerate() {
+            let previous = input[(j + input.len() - 1) % input.len()];
+            let (side_current, side_previous) = (side(current), side(previous));
+            if (side_current >= 0.0) != (side_previous >= 0.0) {
+                let t = side_previous / (side_previous - side_current);
+                clipped.push(previous + (current - previous) * t);
+            }
+            if side_current >= 0.0 {
+                clipped.push(current);
+            }
+        }
+        if clipped.is_empty() {
+            break;
+        }
+    }
+    clipped
+}
+
+/// Unsigned area and centroid of a simple polygon in either winding
+fn area_and_centroid(polygon: &[Point2<f64>]) -> (f64, Point2<f64>) {
+    if polygon.len() < 3 {
+        return (0.0, Point2::origin());
+    }
+    // Relative to the first vertex for precision far from the origin
+    let origin = polygon[0];
+    let (mut twice_area, mut moment) = (0.0, Vector2::zeros());
+    for i in 1..polygon.len() - 1 {
+        let (a, b) = (polygon[i] - origin, polygon[i + 1] - origin);
+        let cross = a.x * b.y - a.y * b.x;
+        twice_area += cross;
+        moment += (a + b) * cross;
+    }
+    if twice_area.abs() < 1e-12 {
+        return (0.0, origin);
+    }
+    (twice_area.abs() / 2.0, origin + moment / (3.0 * twice_area))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use crate::physics::bodies::{BodyType, CompoundChild, Material};
+
+    fn water() -> FluidVolume {
+        // Surface at y = 0
+        FluidVolume::rectangle(Point2::new(0.0, -5.0), 20.0, 10.0, 1.0).unwrap()
+    }
+
+    #[test]
+    fn test_submerged_rectangle_and_circle() {
+        let rectangle = Shape::Rectangle { width: 2.0, height: 1.0 };
+        let half = water().submerged(&rectangle, Point2::new(0.0, 0.2)).unwrap();
+        assert!((half.area - 0.6).abs() < 1e-10);
+        assert!((half.centroid - Point2::new(0.0, -0.15)).norm() < 1e-10);
+        assert!(water().submerged(&rectangle, Point2::new(0.0, 0.6)).is_none());
+
+        // A circle half under the surface: area of a half disc, centroid 4r/3pi below the surface
+        let circle = Shape::Circle { radius: 1.0 };
+        let half_disc = water().submerged(&circle, Point2::new(3.0, 0.0)).unwrap();
+        assert!((half_disc.area - PI / 2.0).abs() < 1e-10);
+        assert!((half_disc.centroid - Point2::new(3.0, -4.0 / (3.0 * PI))).norm() < 1e-10);
+        let full = water().submerged(&circle, Point2::new(3.0, -2.0)).unwrap();
+        assert!((full.area - PI).abs() < 1e-10);
+        assert_eq!(full.centroid, Point2::new(3.0, -2.0));
+        assert!(water().submerged(&circle, Point2::new(3.0, 1.0)).is_none());
+
+        // Mostly under: the circle minus the segment of height 0.5 above the surface
+        let mostly = water().submerged(&circle, Point2::new(3.0, -0.5)).unwrap();
+        let cap_area = (0.5f64).acos() - 0.5 * 0.75f64.sqrt();
+        assert!((mostly.area - (PI - cap_area)).abs() < 1e-10);
+        let cap_centroid = 2.0 / 3.0 * 0.75f64.powf(1.5) / cap_area;
+        assert!((mostly.centroid.y - (-0.5 - cap_centroid * cap_area / (PI - cap_area))).abs() < 1e-10);
+
+        // Near a corner of the pool it is clipped as a polygon instead
+        let corner = water().submerged(&circle, Point2::new(10.0, 0.0)).unwrap();
+        assert!((corner.area - PI / 4.0).abs() < 0.01);
+
+        // Compounds add up their children
+        let compound = Shape::Compound {
+            children: vec![
+                CompoundChild { shape: rectangle.clone(), offset: Vector2::new(-1.0, 0.0) },
+                CompoundChild { shape: rectangle, offset: Vector2::new(1.0, -1.0) },
+            ],
+        };
+        let both = water().submerged(&compound, Point2::new(0.0, 0.2)).unwrap();
+        assert!((both.area - 2.6).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_polygon_region() {
+        // A triangular pool, given clockwise
+        let pool = FluidVolume::polygon(&[Point2::new(0.0, 0.0), Point2::new(-2.0, 2.0), Point2::new(2.0, 2.0)], 1.0).unwrap();
+        let submerged = pool.submerged(&Shape::Rectangle { width: 4.0, height: 1.0 }, Point2::new(0.0, 1.5)).unwrap();
+        assert!((submerged.area - 3.0).abs() < 1e-10);
+        assert_eq!(
+            FluidVolume::polygon(&[Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)], 1.0),
+            Err(FluidError::Region(DecompositionError::TooFewVertices))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fluids_are_rejected() {
+        let center = Point2::new(0.0, 0.0);
+        assert_eq!(FluidVolume::rectangle(center, 0.0, 1.0, 1.0), Err(FluidError::InvalidSize));
+        assert_eq!(FluidVolume::rectangle(center, 1.0, -1.0, 1.0), Err(FluidError::InvalidSize));
+        assert_eq!(FluidVolume::rectangle(center, f64::NAN, 1.0, 1.0), Err(FluidError::InvalidSize));
+        assert_eq!(FluidVolume::rectangle(center, 1.0, 1.0, -1.0), Err(FluidError::InvalidDensity));
+        assert_eq!(FluidVolume::rectangle(center, 1.0, 1.0, f64::INFINITY), Err(FluidError::InvalidDensity));
+        let triangle = [Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(0.0, 1.0)];
+        assert_eq!(FluidVolume::polygon(&triangle, f64::NAN), Err(FluidError::InvalidDensity));
+        // A density-free region still applies drag
+        assert!(FluidVolume::rectangle(center, 1.0, 1.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_buoyancy_and_drag_forces() {
+        let gravity = Vector2::new(0.0, -10.0);
+        let mut body = Body::new_rectangle(Point2::new(0.0, 0.0), 2.0, 1.0, Material::wood(), BodyType::Dynamic);
+        let (force, torque) = water().force_and_torque(&body, gravity);
+        assert!((force - Vector2::new(0.0, 10.0)).norm() < 1e-10);
+        assert!(torque.abs() < 1e-10);
+
+        // Only the left half is in the pool: buoyancy acts off-center and turns the body clockwise
+        body.position.x = 10.0;
+        let (force, torque) = water().force_and_torque(&body, gravity);
+        assert!((force.y - 5.0).abs() < 1e-10);
+        assert!((torque + 2.5).abs() < 1e-10);
+
+        body.position.x = 0.0;
+        body.velocity = Vector2::new(1.0, 0.0);
+        let river = water().with_drag(2.0, 1.0).with_flow(Vector2::new(3.0, 0.0));
+        let (force, _) = river.force_and_torque(&body, gravity);
+        assert!((force - Vector2::new(2.0 * (2.0 + 2.0), 10.0)).norm() < 1e-10);
+
+        body.velocity = river.flow_velocity;
+        body.angular_velocity = 1.0;
+        let (_, torque) = river.force_and_torque(&body, gravity);
+        assert!((torque + 2.0 / (2.0 * PI)).abs() < 1e-10);
+
+        // Buoyancy follows the body's gravity scale like its weight does
+        body.angular_velocity = 0.0;
+        body.gravity_scale = 0.5;
+        let (force, _) = water().force_and_torque(&body, gravity);
+        assert!((force - Vector2::new(0.0, 5.0)).norm() < 1e-10);
+        body.gravity_scale = 0.0;
+        assert_eq!(water().force_and_torque(&body, gravity).0, Vector2::zeros());
+    }
+}
@@ -0,0 +1,476 @@
+//! Support-function based narrow phase for any pair of convex shapes:
+//! GJK finds the distance between two shapes or proves they overlap,
+//! EPA then recovers the penetration normal and depth.
+
+use nalgebra::{Point2, Vector2};
+use crate::physics::bodies::Shape;
+use crate::physics::collisions::{polygon_vertices, rounded_segment, Contact};
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f64 = 1e-10;
+
+/// A convex shape described as the convex hull of a few core points, inflated by a radius.
+/// Rounding is kept out of the core so GJK and EPA only ever see polygons.
+#[derive(Debug, Clone)]
+pub struct ConvexCore {
+    /// World-space points whose convex hull is the core
+    pub points: Vec<Point2<f64>>,
+    /// Distance the core is inflated by
+    pub radius: f64,
+}
+
+impl ConvexCore {
+    /// Describes a convex shape placed at `position`, or `None` for chains, height fields and compounds
+    pub fn from_shape(shape: &Shape, position: Point2<f64>) -> Option<Self> {
+        if let Some(points) = polygon_vertices(shape, position) {
+            return Some(Self { points, radius: 0.0 });
+        }
+        let (a, b, radius) = rounded_segment(shape, position)?;
+        Some(Self { points: vec![a, b], radius })
+    }
+
+    /// Core point furthest along `direction`
+    pub fn support(&self, direction: Vector2<f64>) -> Point2<f64> {
+        *self
+            .points
+            .iter()
+            .max_by(|p, q| p.coords.dot(&direction).total_cmp(&q.coords.dot(&direction)))
+            .expect("convex core has points")
+    }
+}
+
+/// A point of the Minkowski difference `a - b` together with the points it came from
+#[derive(Debug, Clone, Copy)]
+struct SimplexVertex {
+    a: Point2<f64>,
+    b: Point2<f64>,
+    w: Vector2<f64>,
+}
+
+fn support(a: &ConvexCore, b: &ConvexCore, direction: Vector2<f64>) -> SimplexVertex {
+    let point_a = a.support(direction);
+    let point_b = b.support(-direction);
+    SimplexVertex { a: point_a, b: point_b, w: point_a - point_b }
+}
+
+enum GjkOutcome {
+    /// Closest point of the Minkowski difference to the origin, with the simplex and weights producing it
+    Separated(Vector2<f64>, Vec<SimplexVertex>, Vec<f64>),
+    /// The origin lies in the Minkowski difference; the final simplex seeds EPA
+    Overlapping(Vec<SimplexVertex>),
+}
+
+fn gjk(a: &ConvexCore, b: &ConvexCore) -> GjkOutcome {
+    let mut simplex = vec![support(a, b, Vector2::new(1.0, 0.0))];
+    let mut result = (simplex[0].w, simplex.clone(), vec![1.0]);
+
+    for _ in 0..MAX_ITERATIONS {
+        let (closest, reduced, weights) = closest_on_simplex(&simplex);
+        if reduced.len() == 3 || closest.norm() < TOLERANCE {
+            return GjkOutcome::Overlapping(reduced);
+        }
+        result = (closest, reduced.clone(), weights);
+
+        // Stop once the new support point brings the simplex no closer to the origin
+        let direction = -closest;
+        let vertex = support(a, b, direction);
+        let progress = vertex.w.dot(&direction) - closest.dot(&direction);
+        if progress <= TOLERANCE * closest.norm()
+            || reduced.iter().any(|v| (v.w - vertex.w).norm_squared() < TOLERANCE * TOLERANCE)
+        {
+            break;
+        }
+        simplex = reduced;
+        simplex.push(vertex);
+    }
+
+    let (closest, simplex, weights) = result;
+    GjkOutcome::Separated(closest, simplex, weights)
+}
+
+/// Closest point of a simplex (1 to 3 vertices) to the origin, reduced to the vertices that
+/// support it and their barycentric weights. A triangle is kept only when it contains the origin.
+fn closest_on_simplex(simplex: &[SimplexVertex]) -> (Vector2<f64>, Vec<SimplexVertex>, Vec<f64>) {
+    match simplex {
+        [v] => (v.w, vec![*v], vec![1.0]),
+        [v0, v1] => {
+            let edge = v1.w - v0.w;
+            let length_sq = edge.norm_squared();
+            let t = if length_sq < TOLERANCE * TOLERANCE { 0.0 } else { -v0.w.dot(&edge) / length_sq };
+            if t <= 0.0 {
+                (v0.w, vec![*v0], vec![1.0])
+            } else if t >= 1.0 {
+                (v1.w, vec![*v1], vec![1.0])
+            } else {
+                (v0.w + edge * t, vec![*v0, *v1], vec![1.0 - t, t])
+            }
+        }
+        [v0, v1, v2] => {
+            let area = cross(v1.w - v0.w, v2.w - v0.w);
+            if area.abs() > TOLERANCE * TOLERANCE {
+                let side = |p: &SimplexVertex, q: &SimplexVertex| cross(q.w - p.w, -p.w) * area.signum();
+                if side(v0, v1) >= 0.0 && side(v1, v2) >= 0.0 && side(v2, v0) >= 0.0 {
+                    return (Vector2::zeros(), simplex.to_vec(), vec![1.0 / 3.0; 3]);
+                }
+            }
+            [[*v0, *v1], [*v1, *v2], [*v2, *v0]]
+                .iter()
+                .map(|edge| closest_on_simplex(edge))
+                .min_by(|x, y| x.0.norm_squared().total_cmp(&y.0.norm_squared()))
+                .expect("triangle has edges")
+        }
+        _ => unreachable!("simplex has 1 to 3 vertices"),
+    }
+}
+
+/// Expands the overlapping GJK simplex into the edge of the Minkowski difference nearest
+/// to the origin. Returns its outward normal (pointing from `a` towards `b`) and distance.
+fn epa(a: &ConvexCore, b: &ConvexCore, simplex: &[SimplexVertex]) -> (Vector2<f64>, f64) {
+    let mut points: Vec<Vector2<f64>> = simplex.iter().map(|v| v.w).collect();
+    if simplex.len() < 3 {
+        // The origin sits on a vertex or edge: widen the seed so it encloses the origin
+        let mut directions = vec![
+            Vector2::new(1.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(0.0, -1.0),
+        ];
+        if let [v0, v1] = simplex {
+            let edge = v1.w - v0.w;
+            directions.push(Vector2::new(-edge.y, edge.x));
+            directions.push(Vector2::new(edge.y, -edge.x));
+        }
+        points.extend(directions.into_iter().map(|d| support(a, b, d).w));
+    }
+
+    let mut polytope = convex_hull(points);
+    if polytope.len() < 2 {
+        // Both cores are the same single point; any direction separates them
+        return (Vector2::new(0.0, 1.0), 0.0);
+    }
+
+    let mut nearest = nearest_edge(&polytope);
+    for _ in 0..MAX_ITERATIONS {
+        let (index, normal, distance) = nearest;
+        let vertex = support(a, b, normal).w;
+        if vertex.dot(&normal) - distance <= TOLERANCE {
+            break;
+        }
+        polytope.insert(index + 1, vertex);
+        nearest = nearest_edge(&polytope);
+    }
+
+    let (_, normal, distance) = nearest;
+    (normal, distance.max(0.0))
+}
+
+/// Index, outward normal and origin distance of the polytope edge closest to the origin
+fn nearest_edge(polytope: &[Vector2<f64>]) -> (usize, Vector2<f64>, f64) {
+    (0..polytope.len())
+        .filter_map(|i| {
+            let edge = polytope[(i + 1) % polytope.len()] - polytope[i];
+            if edge.norm_squared() < TOLERANCE * TOLERANCE {
+                return None;
+            }
+            let normal = Vector2::new(edge.y, -edge.x).normalize();
+            Some((i, normal, normal.dot(&polytope[i])))
+        })
+        .min_by(|x, y| x.2.total_cmp(&y.2))
+        .expect("polytope has a non-degenerate edge")
+}
+
+/// Counter-clockwise convex hull (monotone chain). Collinear input collapses to its two end points.
+pub(crate) fn convex_hull(mut points: Vec<Vector2<f64>>) -> Vec<Vector2<f64>> {
+    points.sort_by(|p, q| p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y)));
+    points.dedup_by(|p, q| (*p - *q).norm_squared() < TOLERANCE * TOLERANCE);
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vector2<f64>> = Vec::with_capacity(points.len() + 1);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for p in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 1] - hull[hull.len() - 2], p - hull[hull.len() - 2]) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+/// Points on each core producing the simplex's closest point to the origin
+fn witness_points(simplex: &[SimplexVertex], weights: &[f64]) -> (Point2<f64>, Point2<f64>) {
+    let point_a = simplex.iter().zip(weights).fold(Vector2::zeros(), |sum, (v, w)| sum + v.a.coords * *w);
+    let point_b = simplex.iter().zip(weights).fold(Vector2::zeros(), |sum, (v, w)| sum + v.b.coords * *w);
+    (Point2::from(point_a), Point2::from(point_b))
+}
+
+/// Closest points between the cores of two separated convex shapes, or `None` if the cores overlap
+pub fn closest_points(a: &ConvexCore, b: &ConvexCore) -> Option<(Point2<f64>, Point2<f64>)> {
+    match gjk(a, b) {
+        GjkOutcome::Separated(_, simplex, weights) => Some(witness_points(&simplex, &weights)),
+        GjkOutcome::Overlapping(_) => None,
+    }
+}
+
+/// Contact between two convex shapes, normal pointing from `a` to `b`
+pub fn collide(a: &ConvexCore, b: &ConvexCore) -> Option<Contact> {
+    let radius = a.radius + b.radius;
+    match gjk(a, b) {
+        GjkOutcome::Separated(closest, ..) => {
+            let distance = closest.norm();
+            (distance < radius).then(|| Contact::new(-closest / distance, radius - distance))
+        }
+        GjkOutcome::Overlapping(simplex) => {
+            let (normal, depth) = epa(a, b, &simplex);
+            let depth = depth + radius;
+            (depth > 0.0).then(|| Contact::new(normal, depth))
+        }
+    }
+}
+
+/// Signed distance between two convex shapes and the closest point on each surface.
+/// Overlapping shapes get minus their penetration depth, with the deepest point of each
+/// shape inside the other.
+pub fn signed_distance(a: &ConvexCore, b: &ConvexCore) -> (f64, Point2<f64>, Point2<f64>) {
+    match gjk(a, b) {
+        GjkOutcome::Separated(_, simplex, weights) => {
+            let (point_a, point_b) = witness_points(&simplex, &weights);
+            let gap = point_b - point_a;
+            let distance = gap.norm();
+            let normal = gap / distance;
+            (
+                distance - a.radius - b.radius,
+                point_a + normal * a.radius,
+                point_b - normal * b.radius,
+            )
+        }
+        GjkOutcome::Overlapping(simplex) => {
+            let (normal, depth) = epa(a, b, &simplex);
+            let depth = depth + a.radius + b.radius;
+            let point_a = a.support(normal) + normal * a.radius;
+            (-depth, point_a, point_a - normal * depth)
+        }
+    }
+}
+
+/// First contact found by [`cast`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CastHit {
+    /// Fraction of the translation travelled before touching
+    pub fraction: f64,
+    /// Contact point on the moving shape at the time of impact
+    pub point: Point2<f64>,
+    /// Contact normal pointing from the moving shape towards the stationary one
+    pub normal: Vector2<f64>,
+}
+
+/// Sweeps `a` by `translation` against the stationary `b` using conservative advancement:
+/// the distance between translated convex shapes can shrink no faster than the closing speed
+/// along the current normal, so stepping by `separation / closing speed` never tunnels.
+pub fn cast(a: &ConvexCore, translation: Vector2<f64>, b: &ConvexCore) -> Option<CastHit> {
+    const CAST_TOLERANCE: f64 = 1e-6;
+    let radius = a.radius + b.radius;
+    let mut moved = a.clone();
+    let mut fraction = 0.0;
+
+    for _ in 0..MAX_ITERATIONS {
+        let Some((point_a, point_b)) = closest_points(&moved, b) else {
+            // The cores already overlap; report the penetration at the current fraction
+            let contact = collide(&moved, b)?;
+            let point = moved.support(contact.normal) + contact.normal * moved.radius;
+            return Some(CastHit { fraction, point, normal: contact.normal });
+        };
+
+        let gap = point_b - point_a;
+        let distance = gap.norm();
+        let normal = gap / distance;
+        let separation = distance - radius;
+        if separation <= CAST_TOLERANCE {
+            return Some(CastHit { fraction, point: point_a + normal * moved.radius, normal });
+        }
+
+        let closing_speed = translation.dot(&normal);
+        if closing_speed <= 0.0 {
+            return None;
+        }
+        fraction += separation / closing_speed;
+        if fraction > 1.0 {
+            return None;
+        }
+        moved.points = a.points.iter().map(|p| p + translation * fraction).collect();
+    }
+    None
+}
+
+/// General narrow phase for any two convex shapes placed at the given positions
+pub fn collide_convex_shapes(
+    shape_a: &Shape,
+    position_a: Point2<f64>,
+    shape_b: &Shape,
+    position_b: Point2<f64>,
+) -> Option<Contact> {
+    let a = ConvexCore::from_shape(shape_a, position_a)?;
+    let b = ConvexCore::from_shape(shape_b, position_b)?;
+    collide(&a, &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::collisions::collide_shapes;
+
+    /// Small deterministic generator so the property tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn range(&mut self, min: f64, max: f64) -> f64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            min + (max - min) * ((self.0 >> 11) as f64 / (1u64 << 53) as f64)
+        }
+
+        fn point(&mut self, extent: f64) -> Point2<f64> {
+            Point2::new(self.range(-extent, extent), self.range(-extent, extent))
+        }
+    }
+
+    #[test]
+    fn test_gjk_matches_circle_circle() {
+        let mut rng = Lcg(1);
+        for _ in 0..1000 {
+            let (r1, r2) = (rng.range(0.1, 3.0), rng.range(0.1, 3.0));
+            let (p1, p2) = (rng.point(5.0), rng.point(5.0));
+            let gap = (p2 - p1).norm() - (r1 + r2);
+            if gap.abs() < 1e-6 || (p2 - p1).norm() < 1e-6 {
+                continue;
+            }
+
+            let (a, b) = (Shape::Circle { radius: r1 }, Shape::Circle { radius: r2 });
+            let expected = collide_shapes(&a, p1, &b, p2);
+            let actual = collide_convex_shapes(&a, p1, &b, p2);
+            assert_eq!(expected.is_some(), actual.is_some(), "circles at {} and {}", p1, p2);
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                assert!((expected.normal - actual.normal).norm() < 1e-9);
+                assert!((expected.depth - actual.depth).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gjk_matches_aabb() {
+        let mut rng = Lcg(2);
+        for _ in 0..1000 {
+            let (w1, h1, w2, h2) = (rng.range(0.2, 4.0), rng.range(0.2, 4.0), rng.range(0.2, 4.0), rng.range(0.2, 4.0));
+            let (p1, p2) = (rng.point(4.0), rng.point(4.0));
+            let diff = p2 - p1;
+            let overlap_x = (w1 + w2) / 2.0 - diff.x.abs();
+            let overlap_y = (h1 + h2) / 2.0 - diff.y.abs();
+            if overlap_x.abs() < 1e-6 || overlap_y.abs() < 1e-6 || (overlap_x - overlap_y).abs() < 1e-6 {
+                continue;
+            }
+
+            let a = Shape::Rectangle { width: w1, height: h1 };
+            let b = Shape::Rectangle { width: w2, height: h2 };
+            let expected = collide_shapes(&a, p1, &b, p2);
+            let actual = collide_convex_shapes(&a, p1, &b, p2);
+            assert_eq!(expected.is_some(), actual.is_some(), "boxes at {} and {}", p1, p2);
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                assert!((expected.normal - actual.normal).norm() < 1e-9);
+                assert!((expected.depth - actual.depth).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gjk_matches_polygon_sat() {
+        let mut rng = Lcg(3);
+        let random_polygon = |rng: &mut Lcg| {
+            let count = rng.range(3.0, 8.0) as usize;
+            let radius = rng.range(0.5, 2.0);
+            let mut angles: Vec<f64> = (0..count).map(|_| rng.range(0.0, std::f64::consts::TAU)).collect();
+            angles.sort_by(f64::total_cmp);
+            angles.dedup_by(|x, y| (*x - *y).abs() < 0.05);
+            Shape::Polygon {
+                vertices: angles.iter().map(|t| Point2::new(t.cos(), t.sin()) * radius).collect(),
+            }
+        };
+
+        let mut compared = 0;
+        for _ in 0..1000 {
+            let (a, b) = (random_polygon(&mut rng), random_polygon(&mut rng));
+            let (p1, p2) = (rng.point(2.0), rng.point(2.0));
+            let (Shape::Polygon { vertices: va }, Shape::Polygon { vertices: vb }) = (&a, &b) else { unreachable!() };
+            if va.len() < 3 || vb.len() < 3 {
+                continue;
+            }
+
+            let expected = collide_shapes(&a, p1, &b, p2);
+            let actual = collide_convex_shapes(&a, p1, &b, p2);
+            match (expected, actual) {
+                (Some(expected), Some(actual)) => {
+                    assert!((expected.depth - actual.depth).abs() < 1e-8, "depth {} vs {}", expected.depth, actual.depth);
+                    compared += 1;
+                }
+                (None, None) => {}
+                (expected, actual) => {
+                    // Disagreement is only acceptable for grazing contacts
+                    let depth = expected.or(actual).unwrap().depth;
+                    assert!(depth < 1e-8, "only one routine reported a contact of depth {}", depth);
+                }
+            }
+        }
+        assert!(compared > 100);
+    }
+
+    #[test]
+    fn test_cast_stops_at_first_contact() {
+        let ground = ConvexCore::from_shape(&Shape::Rectangle { width: 10.0, height: 2.0 }, Point2::new(0.0, -1.0)).unwrap();
+        let circle = ConvexCore::from_shape(&Shape::Circle { radius: 0.5 }, Point2::new(3.0, 4.5)).unwrap();
+
+        // Dropping diagonally: touches after travelling 4 of the 8 units down
+        let hit = cast(&circle, Vector2::new(-2.0, -8.0), &ground).unwrap();
+        assert!((hit.fraction - 0.5).abs() < 1e-5);
+        assert!((hit.normal - Vector2::new(0.0, -1.0)).norm() < 1e-9);
+        assert!((hit.point - Point2::new(2.0, 0.0)).norm() < 1e-5);
+
+        assert!(cast(&circle, Vector2::new(0.0, -3.0), &ground).is_none());
+        assert!(cast(&circle, Vector2::new(0.0, 8.0), &ground).is_none());
+
+        // A capsule sunk into the ground moving sideways hits the side face
+        let capsule = ConvexCore::from_shape(&Shape::Capsule { half_length: 1.0, radius: 0.5 }, Point2::new(-8.0, 0.5)).unwrap();
+        let hit = cast(&capsule, Vector2::new(6.0, 0.0), &ground).unwrap();
+        assert!((hit.fraction - 2.5 / 6.0).abs() < 1e-5);
+        assert!((hit.normal - Vector2::new(1.0, 0.0)).norm() < 1e-9);
+
+        // Starting inside reports an immediate hit
+        let buried = ConvexCore::from_shape(&Shape::Circle { radius: 0.5 }, Point2::new(0.0, -0.2)).unwrap();
+        let hit = cast(&buried, Vector2::new(1.0, 0.0), &ground).unwrap();
+        assert_eq!(hit.fraction, 0.0);
+        assert!((hit.normal - Vector2::new(0.0, -1.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_closest_points_between_separated_shapes() {
+        let ground = ConvexCore::from_shape(&Shape::Rectangle { width: 4.0, height: 2.0 }, Point2::new(0.0, 0.0)).unwrap();
+        let capsule = ConvexCore::from_shape(
+            &Shape::Capsule { half_length: 1.0, radius: 0.5 },
+            Point2::new(3.0, 4.0),
+        )
+        .unwrap();
+
+        // The capsule core's lower end is closest to the box corner
+        let (on_box, on_capsule) = closest_points(&ground, &capsule).unwrap();
+        assert!((on_box - Point2::new(2.0, 1.0)).norm() < 1e-10);
+        assert!((on_capsule - Point2::new(3.0, 3.0)).norm() < 1e-10);
+
+        let overlapping = ConvexCore::from_shape(&Shape::Circle { radius: 1.0 }, Point2::new(1.0, 0.5)).unwrap();
+        assert!(closest_points(&ground, &overlapping).is_none());
+    }
+}
@@ -0,0 +1,490 @@
+//! Joint definitions and the velocity solver for them
+
+use nalgebra::{Point2, Rotation2, Vector2};
+use crate::physics::bodies::Body;
+
+/// Fraction of the positional error fed back into the velocity solve each step
+const BAUMGARTE: f64 = 0.2;
+
+/// Constraints connecting bodies, solved together with contacts in `World::update`
+#[derive(Debug, Clone)]
+pub enum Joint {
+    Pulley(PulleyJoint),
+    Prismatic(PrismaticJoint),
+    Revolute(RevoluteJoint),
+    Gear(GearJoint),
+}
+
+/// Two bodies hung over two fixed ground anchors by a single rope.
+/// Keeps `length_a + ratio * length_b` constant.
+#[derive(Debug, Clone)]
+pub struct PulleyJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    /// World-space anchor the rope of body_a runs over
+    pub ground_anchor_a: Point2<f64>,
+    /// World-space anchor the rope of body_b runs over
+    pub ground_anchor_b: Point2<f64>,
+    /// Mechanical advantage of the pulley
+    pub ratio: f64,
+    /// Total rope length (`length_a + ratio * length_b`)
+    pub length: f64,
+}
+
+/// Lets body_b slide relative to body_a along a fixed axis only.
+/// Use a static body as body_a to slide along a world axis.
+#[derive(Debug, Clone)]
+pub struct PrismaticJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    /// Unit slide axis in world space
+    pub axis: Vector2<f64>,
+    /// Offset of body_b from body_a across the axis, kept constant
+    pub perpendicular_offset: f64,
+}
+
+/// Pins body_b to body_a at a shared anchor point, leaving them free to rotate about it.
+/// Use a static body as body_a to hinge a body to the world, like a pendulum or a wheel axle.
+#[derive(Debug, Clone)]
+pub struct RevoluteJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    /// Anchor relative to body_a's center of mass, in the body's unrotated frame
+    pub local_anchor_a: Vector2<f64>,
+    /// Anchor relative to body_b's center of mass, in the body's unrotated frame
+    pub local_anchor_b: Vector2<f64>,
+    /// Angle of body_b relative to body_a when the joint was created
+    pub reference_angle: f64,
+}
+
+/// Couples the coordinates of two other joints so that
+/// `coordinate_a + ratio * coordinate_b` stays constant.
+///
+/// Prismatic joints contribute their translation and revolute joints their angle, so two
+/// revolute joints make a pair of gears and a revolute with a prismatic one a rack and pinion.
+#[derive(Debug, Clone)]
+pub struct GearJoint {
+    /// Index of the first joint in `World::joints`
+    pub joint_a: usize,
+    /// Index of the second joint in `World::joints`
+    pub joint_b: usize,
+    pub ratio: f64,
+    /// Value of `coordinate_a + ratio * coordinate_b` to maintain
+    pub constant: f64,
+}
+
+impl PulleyJoint {
+    /// Creates a pulley joint, taking the rope length from the current centers of mass
+    pub fn new(
+        bodies: &[Body],
+        body_a: usize,
+        body_b: usize,
+        ground_anchor_a: Point2<f64>,
+        ground_anchor_b: Point2<f64>,
+        ratio: f64,
+    ) -> Self {
+        let mut joint = Self {
+            body_a,
+            body_b,
+            ground_anchor_a,
+            ground_anchor_b,
+            ratio,
+            length: 0.0,
+        };
+        let (length_a, length_b) = joint.lengths(bodies);
+        joint.length = length_a + ratio * length_b;
+        joint
+    }
+
+    /// Current rope lengths on each side of the pulley
+    pub fn lengths(&self, bodies: &[Body]) -> (f64, f64) {
+        (
+            (bodies[self.body_a].center_of_mass() - self.ground_anchor_a).norm(),
+            (bodies[self.body_b].center_of_mass() - self.ground_anchor_b).norm(),
+        )
+    }
+}
+
+impl PrismaticJoint {
+    /// Creates a prismatic joint along `axis`, keeping the current sideways offset
+    pub fn new(bodies: &[Body], body_a: usize, body_b: usize, axis: Vector2<f64>) -> Self {
+        let mut joint = Self {
+            body_a,
+            body_b,
+            axis: axis.normalize(),
+            perpendicular_offset: 0.0,
+        };
+        joint.perpendicular_offset =
+            (bodies[body_b].center_of_mass() - bodies[body_a].center_of_mass()).dot(&joint.perpendicular());
+        joint
+    }
+
+    /// Translation of body_b relative to body_a along the axis
+    pub fn coordinate(&self, bodies: &[Body]) -> f64 {
+        (bodies[self.body_b].center_of_mass() - bodies[self.body_a].center_of_mass()).dot(&self.axis)
+    }
+
+    fn perpendicular(&self) -> Vector2<f64> {
+        Vector2::new(-self.axis.y, self.axis.x)
+    }
+}
+
+impl RevoluteJoint {
+    /// Creates a revolute joint pinning the two bodies together at the world-space `anchor`
+    pub fn new(bodies: &[Body], body_a: usize, body_b: usize, anchor: Point2<f64>) -> Self {
+        let local_anchor = |body: &Body| Rotation2::new(-body.angle) * (anchor - body.center_of_mass());
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: local_anchor(&bodies[body_a]),
+            local_anchor_b: local_anchor(&bodies[body_b]),
+            reference_angle: bodies[body_b].angle - bodies[body_a].angle,
+        }
+    }
+
+    /// Rotation of body_b relative to body_a since the joint was created
+    pub fn angle(&self, bodies: &[Body]) -> f64 {
+        bodies[self.body_b].angle - bodies[self.body_a].angle - self.reference_angle
+    }
+
+    /// Anchor arms from each body's center of mass, in world space
+    pub fn arms(&self, bodies: &[Body]) -> (Vector2<f64>, Vector2<f64>) {
+        (
+            Rotation2::new(bodies[self.body_a].angle) * self.local_anchor_a,
+            Rotation2::new(bodies[self.body_b].angle) * self.local_anchor_b,
+        )
+    }
+
+    /// World-space positions of the anchor on each body; they coincide while the joint holds
+    pub fn anchors(&self, bodies: &[Body]) -> (Point2<f64>, Point2<f64>) {
+        let (arm_a, arm_b) = self.arms(bodies);
+        (bodies[self.body_a].center_of_mass() + arm_a, bodies[self.body_b].center_of_mass() + arm_b)
+    }
+}
+
+impl GearJoint {
+    /// Creates a gear joint between two existing joints, keeping their current combined coordinate.
+    /// Returns `None` unless both are prismatic or revolute joints.
+    pub fn new(joints: &[Joint], bodies: &[Body], joint_a: usize, joint_b: usize, ratio: f64) -> Option<Self> {
+        let mut joint = Self {
+            joint_a,
+            joint_b,
+            ratio,
+            constant: 0.0,
+        };
+        joint.constant = joint.value(joints, bodies)?;
+        Some(joint)
+    }
+
+    /// Current value of `coordinate_a + ratio * coordinate_b`, if both joints can be geared
+    pub fn value(&self, joints: &[Joint], bodies: &[Body]) -> Option<f64> {
+        let (a, _) = gear_coordinate(joints.get(self.joint_a)?, bodies)?;
+        let (b, _) = gear_coordinate(joints.get(self.joint_b)?, bodies)?;
+        Some(a + self.ratio * b)
+    }
+}
+
+/// Coordinate a joint contributes to a gear, with its Jacobian entries
+fn gear_coordinate(joint: &Joint, bodies: &[Body]) -> Option<(f64, [JacobianEntry; 2])> {
+    match joint {
+        Joint::Prismatic(prismatic) => Some((
+            prismatic.coordinate(bodies),
+            [(prismatic.body_a, -prismatic.axis, 0.0), (prismatic.body_b, prismatic.axis, 0.0)],
+        )),
+        Joint::Revolute(revolute) => Some((
+            revolute.angle(bodies),
+            [(revolute.body_a, Vector2::zeros(), -1.0), (revolute.body_b, Vector2::zeros(), 1.0)],
+        )),
+        Joint::Pulley(_) | Joint::Gear(_) => None,
+    }
+}
+
+/// Body index with the linear and angular parts of its row in a constraint Jacobian
+type JacobianEntry = (usize, Vector2<f64>, f64);
+
+/// Runs one velocity iteration over all joints
+pub fn solve_joints(joints: &[Joint], bodies: &mut [Body], dt: f64) {
+    for joint in joints {
+        match joint {
+            Joint::Pulley(pulley) => {
+                let (length_a, length_b) = pulley.lengths(bodies);
+                if length_a < 1e-10 || length_b < 1e-10 {
+                    continue;
+                }
+                let u_a = (bodies[pulley.body_a].center_of_mass() - pulley.ground_anchor_a) / length_a;
+                let u_b = (bodies[pulley.body_b].center_of_mass() - pulley.ground_anchor_b) / length_b;
+                let error = length_a + pulley.ratio * length_b - pulley.length;
+                solve_constraint_row(
+                    bodies,
+                    &[(pulley.body_a, u_a, 0.0), (pulley.body_b, u_b * pulley.ratio, 0.0)],
+                    error,
+                    dt,
+                );
+            }
+            Joint::Prismatic(prismatic) => {
+                let perp = prismatic.perpendicular();
+                let error = (bodies[prismatic.body_b].center_of_mass() - bodies[prismatic.body_a].center_of_mass())
+                    .dot(&perp)
+                    - prismatic.perpendicular_offset;
+                solve_constraint_row(
+                    bodies,
+                    &[(prismatic.body_a, -perp, 0.0), (prismatic.body_b, perp, 0.0)],
+                    error,
+                    dt,
+                );
+            }
+            Joint::Revolute(revolute) => {
+                // One row per world axis, each closing the gap between the two anchors
+                for axis in [Vector2::x(), Vector2::y()] {
+                    let (arm_a, arm_b) = revolute.arms(bodies);
+                    let (anchor_a, anchor_b) = revolute.anchors(bodies);
+                    solve_constraint_row(
+                        bodies,
+                        &[
+                            (revolute.body_a, -axis, -cross(arm_a, axis)),
+                            (revolute.body_b, axis, cross(arm_b, axis)),
+                        ],
+                        (anchor_b - anchor_a).dot(&axis),
+                        dt,
+                    );
+                }
+            }
+            Joint::Gear(gear) => {
+                // Gears are only created between gearable joints, but `World::joints` may have changed since
+                let (Some((a, [a0, a1])), Some((b, [b0, b1]))) = (
+                    joints.get(gear.joint_a).and_then(|joint| gear_coordinate(joint, bodies)),
+                    joints.get(gear.joint_b).and_then(|joint| gear_coordinate(joint, bodies)),
+                ) else {
+                    continue;
+                };
+                let scaled = |(index, linear, angular): JacobianEntry| (index, linear * gear.ratio, angular * gear.ratio);
+                solve_constraint_row(
+                    bodies,
+                    &[a0, a1, scaled(b0), scaled(b1)],
+                    a + gear.ratio * b - gear.constant,
+                    dt,
+                );
+            }
+        }
+    }
+}
+
+/// Solves a single scalar constraint `sum(J_i . v_i + a_i * w_i) = -bias` by applying an impulse
+/// along each body's Jacobian row. The same body may appear more than once.
+fn solve_constraint_row(bodies: &mut [Body], jacobian: &[JacobianEntry], error: f64, dt: f64) {
+    // Merge entries referring to the same body so the effective mass is correct
+    let mut rows: Vec<JacobianEntry> = Vec::with_capacity(jacobian.len());
+    for &(index, j, a) in jacobian {
+        match rows.iter_mut().find(|(i, _, _)| *i == index) {
+            Some((_, linear, angular)) => {
+                *linear += j;
+                *angular += a;
+            }
+            None => rows.push((index, j, a)),
+        }
+    }
+
+    let mut effective_mass_inv = 0.0;
+    let mut velocity_error = 0.0;
+    for &(index, j, a) in &rows {
+        let body = &bodies[index];
+        effective_mass_inv += j.dot(&body.inverse_mass_vector().component_mul(&j)) + a * a * body.inverse_inertia();
+        velocity_error += j.dot(&body.velocity) + a * body.angular_velocity;
+    }
+    if effective_mass_inv < 1e-12 {
+        return;
+    }
+
+    let bias = BAUMGARTE / dt * error;
+    let lambda = -(velocity_error + bias) / effective_mass_inv;
+    for &(index, j, a) in &rows {
+        let body = &mut bodies[index];
+        body.velocity += body.inverse_mass_vector().component_mul(&j) * lambda;
+        body.angular_velocity += body.inverse_inertia() * a * lambda;
+    }
+}
+
+fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::World;
+    use crate::physics::bodies::{BodyType, MassProperties, Material};
+
+    #[test]
+    fn test_pulley_ratio_holds() {
+        let mut world = World::new();
+        let a = world.bodies.len();
+        world.add_body(Body::new_circle(Point2::new(-5.0, 0.0), 1.0, Material::stone(), BodyType::Dynamic));
+        let b = world.bodies.len();
+        world.add_body(Body::new_circle(Point2::new(5.0, -5.0), 1.0, Material::wood(), BodyType::Dynamic));
+
+        let ratio = 2.0;
+        let joint = world.add_joint(Joint::Pulley(PulleyJoint::new(
+            &world.bodies,
+            a,
+            b,
+            Point2::new(-5.0, 10.0),
+            Point2::new(5.0, 10.0),
+            ratio,
+        )));
+
+        let Joint::Pulley(pulley) = world.joints[joint].clone() else { unreachable!() };
+        let (start_a, start_b) = pulley.lengths(&world.bodies);
+
+        for _ in 0..120 {
+            world.update(1.0 / 60.0);
+            let (length_a, length_b) = pulley.lengths(&world.bodies);
+            assert!(
+                (length_a + ratio * length_b - pulley.length).abs() < 0.05,
+                "rope length drifted to {}",
+                length_a + ratio * length_b
+            );
+        }
+
+        // The heavier stone body must have pulled the wooden one up
+        let (end_a, end_b) = pulley.lengths(&world.bodies);
+        assert!(end_a > start_a + 1.0);
+        assert!(end_b < start_b - 0.5);
+    }
+
+    #[test]
+    fn test_prismatic_keeps_body_on_axis() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.0), 1.0, 1.0, Material::stone(), BodyType::Static));
+        world.add_body(Body::new_circle(Point2::new(0.0, 5.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.bodies[1].velocity = Vector2::new(3.0, 0.0);
+        world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 1, Vector2::new(1.0, 0.0))));
+
+        for _ in 0..120 {
+            world.update(1.0 / 60.0);
+        }
+
+        assert!((world.bodies[1].position.y - 5.0).abs() < 0.05);
+        assert!(world.bodies[1].position.x > 1.0);
+    }
+
+    #[test]
+    fn test_prismatic_slides_center_of_mass() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.0), 1.0, 1.0, Material::stone(), BodyType::Static));
+        let mut wheel = Body::new_circle(Point2::new(0.0, 5.0), 1.0, Material::wood(), BodyType::Dynamic);
+        wheel.set_mass_properties(MassProperties { mass: 2.0, center_of_mass: Vector2::new(0.5, 0.0), inertia: 1.0 });
+        world.add_body(wheel);
+        world.bodies[1].angular_velocity = 3.0;
+        world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 1, Vector2::new(1.0, 0.0))));
+
+        // The off-center wheel spins freely, so only its center of mass stays on the axis
+        for _ in 0..60 {
+            world.update(1.0 / 60.0);
+            assert!((world.bodies[1].center_of_mass().y - 5.0).abs() < 0.05);
+        }
+        assert!(world.bodies[1].angle > 1.5);
+    }
+
+    #[test]
+    fn test_gear_ratio_holds() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.0), 1.0, 1.0, Material::stone(), BodyType::Static));
+        world.add_body(Body::new_circle(Point2::new(-10.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_circle(Point2::new(10.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+
+        // A vertical slider geared to a horizontal one: gravity drives the first, the gear the second
+        let slider_a = world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 1, Vector2::new(0.0, 1.0))));
+        let slider_b = world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 2, Vector2::new(1.0, 0.0))));
+        let ratio = 3.0;
+        let gear = world.add_joint(Joint::Gear(GearJoint::new(&world.joints, &world.bodies, slider_a, slider_b, ratio).unwrap()));
+
+        let Joint::Gear(gear) = world.joints[gear].clone() else { unreachable!() };
+        for _ in 0..120 {
+            world.update(1.0 / 60.0);
+            let value = gear.value(&world.joints, &world.bodies).unwrap();
+            assert!((value - gear.constant).abs() < 0.05, "gear drifted to {}", value);
+        }
+
+        // The first slider falling must push the second one sideways by a third of the distance
+        let drop = -world.bodies[1].position.y;
+        let shift = world.bodies[2].position.x - 10.0;
+        assert!(drop > 1.0);
+        assert!((shift - drop / ratio).abs() < 0.05);
+        assert!(world.bodies[2].position.y.abs() < 0.05);
+    }
+
+    #[test]
+    fn test_revolute_pendulum_swings_about_anchor() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.0), 0.2, 0.2, Material::stone(), BodyType::Static));
+        world.add_body(Body::new_circle(Point2::new(3.0, 0.0), 0.5, Material::stone(), BodyType::Dynamic));
+        let joint = world.add_joint(Joint::Revolute(RevoluteJoint::new(&world.bodies, 0, 1, Point2::new(0.0, 0.0))));
+
+        let Joint::Revolute(pin) = world.joints[joint].clone() else { unreachable!() };
+        let mut lowest: f64 = 0.0;
+        for _ in 0..60 {
+            world.update(1.0 / 60.0);
+            let (anchor_a, anchor_b) = pin.anchors(&world.bodies);
+            assert!((anchor_a - anchor_b).norm() < 0.1, "pin separated by {}", (anchor_a - anchor_b).norm());
+            lowest = lowest.min(world.bodies[1].position.y);
+        }
+
+        // Swinging down on its arm turns the bob clockwise, past the bottom of the arc
+        assert!(lowest < -2.9);
+        assert!(world.bodies[1].position.x.abs() < 3.0);
+        assert!(pin.angle(&world.bodies) < -1.0);
+    }
+
+    #[test]
+    fn test_geared_wheels_and_rack() {
+        let mut world = World::new();
+        world.gravity = Vector2::zeros();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, -10.0), 1.0, 1.0, Material::stone(), BodyType::Static));
+        world.add_body(Body::new_circle(Point2::new(0.0, 0.0), 2.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_circle(Point2::new(3.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 3.0), 6.0, 1.0, Material::wood(), BodyType::Dynamic));
+
+        // Two wheels on axles and a rack sliding along x above the big one
+        let axle_a = world.add_joint(Joint::Revolute(RevoluteJoint::new(&world.bodies, 0, 1, Point2::new(0.0, 0.0))));
+        let axle_b = world.add_joint(Joint::Revolute(RevoluteJoint::new(&world.bodies, 0, 2, Point2::new(3.0, 0.0))));
+        let slider = world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 3, Vector2::new(1.0, 0.0))));
+        // Meshing gears turn opposite ways, the small one twice as fast; the rack moves with the rim
+        let gears = GearJoint::new(&world.joints, &world.bodies, axle_a, axle_b, 0.5).unwrap();
+        let rack = GearJoint::new(&world.joints, &world.bodies, axle_a, slider, 0.5).unwrap();
+        world.add_joint(Joint::Gear(gears));
+        world.add_joint(Joint::Gear(rack));
+
+        world.bodies[1].angular_velocity = 2.0;
+        for _ in 0..60 {
+            world.update(1.0 / 60.0);
+        }
+
+        let turned = world.bodies[1].angle;
+        assert!(turned > 0.5);
+        assert!((world.bodies[2].angle + 2.0 * turned).abs() < 0.05);
+        assert!((world.bodies[3].position.x + 2.0 * turned).abs() < 0.05);
+        // The axles hold the wheels in place
+        assert!((world.bodies[1].position - Point2::new(0.0, 0.0)).norm() < 0.05);
+        assert!((world.bodies[2].position - Point2::new(3.0, 0.0)).norm() < 0.05);
+    }
+
+    #[test]
+    fn test_gear_rejects_pulleys() {
+        let mut world = World::new();
+        world.add_body(Body::new_circle(Point2::new(-5.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_circle(Point2::new(5.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        let pulley = world.add_joint(Joint::Pulley(PulleyJoint::new(
+            &world.bodies,
+            0,
+            1,
+            Point2::new(-5.0, 5.0),
+            Point2::new(5.0, 5.0),
+            1.0,
+        )));
+        let slider = world.add_joint(Joint::Prismatic(PrismaticJoint::new(&world.bodies, 0, 1, Vector2::new(1.0, 0.0))));
+
+        assert!(GearJoint::new(&world.joints, &world.bodies, pulley, slider, 1.0).is_none());
+        assert!(GearJoint::new(&world.joints, &world.bodies, slider, 7, 1.0).is_none());
+        assert!(GearJoint::new(&world.joints, &world.bodies, slider, slider, 1.0).is_some());
+    }
+}
@@ -0,0 +1,293 @@
+//! Named material libraries, built in or loaded from TOML data files
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use crate::physics::bodies::{CombineRule, Material};
+
+/// Reasons a material library cannot be loaded or a material cannot be found
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialError {
+    /// The file could not be read
+    Io(String),
+    /// The text is not a valid TOML material table
+    Parse(String),
+    /// No material with this name is in the library
+    Unknown(String),
+    /// A coefficient lies outside its sensible range
+    OutOfRange {
+        material: String,
+        property: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// Kinetic friction exceeds static friction, so sliding would grip harder than sticking
+    KineticExceedsStatic { material: String },
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaterialError::Io(message) => write!(f, "could not read material file: {}", message),
+            MaterialError::Parse(message) => write!(f, "invalid material file: {}", message),
+            MaterialError::Unknown(name) => write!(f, "unknown material `{}`", name),
+            MaterialError::OutOfRange { material, property, value, min, max } => write!(
+                f,
+                "material `{}`: {} is {} but must lie between {} and {}",
+                material, property, value, min, max
+            ),
+            MaterialError::KineticExceedsStatic { material } => {
+                write!(f, "material `{}`: kinetic friction exceeds static friction", material)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
+
+/// Checks that every coefficient of `material` lies in a sensible range
+pub fn validate_material(name: &str, material: &Material) -> Result<(), MaterialError> {
+    let mut ranges = vec![
+        ("density", material.density, f64::MIN_POSITIVE, 100.0),
+        ("restitution", material.restitution, 0.0, 1.0),
+        ("friction", material.friction, 0.0, 2.0),
+        ("kinetic_friction", material.kinetic_friction, 0.0, 2.0),
+        ("rolling_resistance", material.rolling_resistance, 0.0, 1.0),
+        ("torsional_friction", material.torsional_friction, 0.0, 1.0),
+    ];
+    if let Some(anisotropy) = material.anisotropic_friction {
+        // A zero direction normalizes to NaN
+        ranges.push(("anisotropic_friction.direction", anisotropy.direction.norm(), 0.5, 1.5));
+        ranges.push(("anisotropic_friction.along", anisotropy.along, 0.0, 10.0));
+        ranges.push(("anisotropic_friction.across", anisotropy.across, 0.0, 10.0));
+    }
+    for (property, value, min, max) in ranges {
+        // Written so that NaN fails too
+        if !(value >= min && value <= max) {
+            return Err(MaterialError::OutOfRange { material: name.to_string(), property, value, min, max });
+        }
+    }
+    if material.kinetic_friction > material.friction {
+        return Err(MaterialError::KineticExceedsStatic { material: name.to_string() });
+    }
+    Ok(())
+}
+
+/// A set of validated materials referenced by name
+#[derive(Debug, Clone, Default)]
+pub struct MaterialLibrary {
+    materials: BTreeMap<String, Material>,
+}
+
+/// One material table as written in a TOML file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialSpec {
+    density: f64,
+    restitution: f64,
+    friction: f64,
+    kinetic_friction: Option<f64>,
+    #[serde(default)]
+    rolling_resistance: f64,
+    #[serde(default)]
+    torsional_friction: f64,
+    anisotropic_friction: Option<AnisotropySpec>,
+    friction_combine: Option<CombineRule>,
+    restitution_combine: Option<CombineRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnisotropySpec {
+    direction: [f64; 2],
+    along: f64,
+    across: f64,
+}
+
+impl MaterialSpec {
+    fn into_material(self) -> Material {
+        let mut material = Material::new(self.density, self.restitution, self.friction)
+            .with_kinetic_friction(self.kinetic_friction.unwrap_or(self.friction))
+            .with_rolling_resistance(self.rolling_resistance)
+            .with_torsional_friction(self.torsional_friction)
+            .with_friction_combine(self.friction_combine.unwrap_or(CombineRule::Average))
+            .with_restitution_combine(self.restitution_combine.unwrap_or(CombineRule::Average));
+        if let Some(anisotropy) = self.anisotropic_friction {
+            let direction = Vector2::new(anisotropy.direction[0], anisotropy.direction[1]);
+            material = material.with_anisotropic_friction(direction, anisotropy.along, anisotropy.across);
+        }
+        material
+    }
+}
+
+impl MaterialLibrary {
+    /// Creates an empty library
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a library holding every predefined material under its snake_case name
+    pub fn builtin() -> Self {
+        let mut library = Self::new();
+        for (name, material) in [
+            ("wood", Material::wood()),
+            ("stone", Material::stone()),
+            ("rubber", Material::rubber()),
+            ("ice", Material::ice()),
+            ("steel", Material::steel()),
+            ("glass", Material::glass()),
+            ("concrete", Material::concrete()),
+            ("bouncy_ball", Material::bouncy_ball()),
+        ] {
+            library.materials.insert(name.to_string(), material);
+        }
+        library
+    }
+
+    /// Parses a library from TOML text with one table per material, e.g.
+    ///
+    /// ```toml
+    /// [ice]
+    /// density = 0.9
+    /// restitution = 0.05
+    /// friction = 0.1
+    /// kinetic_friction = 0.03  # defaults to `friction`
+    /// friction_combine = "min" # average, geometric_mean, min, multiply or max
+    /// ```
+    pub fn from_toml(text: &str) -> Result<Self, MaterialError> {
+        let mut library = Self::new();
+        library.extend_from_toml(text)?;
+        Ok(library)
+    }
+
+    /// Reads a TOML library from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MaterialError> {
+        let text = std::fs::read_to_string(path).map_err(|error| MaterialError::Io(error.to_string()))?;
+        Self::from_toml(&text)
+    }
+
+    /// Adds the materials from TOML text, replacing any with the same name.
+    /// Nothing is added unless every material is valid.
+    pub fn extend_from_toml(&mut self, text: &str) -> Result<(), MaterialError> {
+        let specs: BTreeMap<String, MaterialSpec> =
+            toml::from_str(text).map_err(|error| MaterialError::Parse(error.to_string()))?;
+        let materials = specs
+            .into_iter()
+            .map(|(name, spec)| {
+                let material = spec.into_material();
+                validate_material(&name, &material).map(|()| (name, material))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.materials.extend(materials);
+        Ok(())
+    }
+
+    /// Adds a material after validating it, replacing any with the same name
+    pub fn insert(&mut self, name: impl Into<String>, material: Material) -> Result<(), MaterialError> {
+        let name = name.into();
+        validate_material(&name, &material)?;
+        self.materials.insert(name, material);
+        Ok(())
+    }
+
+    /// Returns a copy of the named material, ready to pass to a body constructor
+    pub fn get(&self, name: &str) -> Result<Material, MaterialError> {
+        self.materials
+            .get(name)
+            .cloned()
+            .ok_or_else(|| MaterialError::Unknown(name.to_string()))
+    }
+
+    /// Whether a material with this name is in the library
+    pub fn contains(&self, name: &str) -> bool {
+        self.materials.contains_key(name)
+    }
+
+    /// Names of all materials, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.materials.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Point2;
+    use crate::physics::bodies::{Body, BodyType};
+
+    #[test]
+    fn test_builtin_materials_are_valid() {
+        let library = MaterialLibrary::builtin();
+        assert_eq!(library.names().count(), 8);
+        for name in library.names() {
+            validate_material(name, &library.get(name).unwrap()).unwrap();
+        }
+        assert!(library.get("ice").unwrap().kinetic_friction < library.get("steel").unwrap().kinetic_friction);
+        assert_eq!(library.get("lava").unwrap_err(), MaterialError::Unknown("lava".to_string()));
+    }
+
+    #[test]
+    fn test_load_from_toml() {
+        let mut library = MaterialLibrary::builtin();
+        library
+            .extend_from_toml(
+                r#"
+                [wood]
+                density = 0.7
+                restitution = 0.2
+                friction = 0.4
+
+                [track]
+                density = 1.0
+                restitution = 0.0
+                friction = 0.3
+                kinetic_friction = 0.1
+                rolling_resistance = 0.05
+                torsional_friction = 0.01
+                friction_combine = "min"
+                restitution_combine = "geometric_mean"
+                anisotropic_friction = { direction = [1.0, 0.0], along = 0.1, across = 1.0 }
+                "#,
+            )
+            .unwrap();
+
+        let wood = library.get("wood").unwrap();
+        assert_eq!((wood.density, wood.friction, wood.kinetic_friction), (0.7, 0.4, 0.4));
+        let track = library.get("track").unwrap();
+        assert_eq!((track.kinetic_friction, track.torsional_friction), (0.1, 0.01));
+        assert_eq!(track.friction_combine, CombineRule::Min);
+        assert_eq!(track.restitution_combine, CombineRule::GeometricMean);
+        assert_eq!(track.anisotropic_friction.unwrap().across, 1.0);
+        assert!(library.contains("steel"));
+
+        let body = Body::new_circle(Point2::new(0.0, 0.0), 1.0, library.get("track").unwrap(), BodyType::Dynamic);
+        assert!((body.mass - std::f64::consts::PI).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_invalid_materials_are_rejected() {
+        let mut library = MaterialLibrary::builtin();
+        let error = library
+            .extend_from_toml("[flubber]\ndensity = 1.0\nrestitution = 1.5\nfriction = 0.5\n")
+            .unwrap_err();
+        assert!(matches!(error, MaterialError::OutOfRange { property: "restitution", .. }));
+        assert!(!library.contains("flubber"));
+
+        let error = library
+            .insert("sandpaper", Material::new(1.0, 0.0, 0.5).with_kinetic_friction(0.9))
+            .unwrap_err();
+        assert_eq!(error, MaterialError::KineticExceedsStatic { material: "sandpaper".to_string() });
+        assert!(library.insert("void", Material::new(0.0, 0.0, 0.5)).is_err());
+        assert!(library.insert("nan", Material::new(1.0, f64::NAN, 0.5)).is_err());
+
+        assert!(matches!(MaterialLibrary::from_toml("[glue]\ndensity = 1.0\n"), Err(MaterialError::Parse(_))));
+        assert!(matches!(
+            MaterialLibrary::from_toml("[glue]\ndensity = 1.0\nrestitution = 0.0\nfriction = 1.0\nstickiness = 3.0\n"),
+            Err(MaterialError::Parse(_))
+        ));
+        assert!(matches!(MaterialLibrary::load("/nonexistent/materials.toml"), Err(MaterialError::Io(_))));
+    }
+}
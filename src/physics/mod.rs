@@ -1,11 +1,28 @@
 // Exports submodules
 
+pub mod aerodynamics;
 pub mod bodies;
 // pub mod integrator; // Removed
 pub mod collisions;
+pub mod decomposition;
+pub mod fields;
+pub mod fluids;
+pub mod gjk;
+pub mod joints;
+pub mod materials;
+pub mod nbody;
+pub mod queries;
 
-use bodies::{Body, BodyType};
-use collisions::{detect_collisions, resolve_collisions};
+use aerodynamics::aerodynamic_force;
+use bodies::{Body, BodyDefaults, BodyType};
+use std::collections::HashMap;
+use collisions::{
+    correct_positions, detect_collisions, remove_one_way_passes, resolve_collisions, update_one_way_contacts, Collision,
+};
+use fields::ForceField;
+use fluids::FluidVolume;
+use joints::{solve_joints, Joint};
+use nbody::NBodyGravity;
 
 /// Represents the physics world that contains all bodies and handles simulation
 pub struct World {
@@ -13,6 +30,28 @@ pub struct World {
     pub bodies: Vec<Body>,
     /// Gravity vector
     pub gravity: nalgebra::Vector2<f64>,
+    /// Joints constraining the bodies, solved alongside contacts
+    pub joints: Vec<Joint>,
+    /// Damping and speed limits for bodies that do not set their own
+    pub body_defaults: BodyDefaults,
+    /// Contacts approaching slower than this (m/s) do not bounce, so resting bodies settle. Defaults to
+    /// zero, which keeps every contact's restitution; raise it (e.g. to 1.0) to stop resting jitter
+    pub restitution_threshold: f64,
+    /// Mutual gravitation between bodies, applied alongside the uniform `gravity` (set that to zero
+    /// for orbital mechanics)
+    pub n_body_gravity: Option<NBodyGravity>,
+    /// Force fields applied to dynamic bodies alongside gravity
+    pub force_fields: Vec<Box<dyn ForceField>>,
+    /// Simulated time in seconds, advanced by every update
+    pub time: f64,
+    /// Density of the air acting on bodies with aerodynamics, in the units of material densities
+    /// (water is about 1.0)
+    pub air_density: f64,
+    /// Fluid volumes applying buoyancy and drag to the bodies in them
+    pub fluids: Vec<FluidVolume>,
+    /// `(one-way body, other body)` pairs in contact, by index into `bodies`, and whether each is
+    /// passing through; pairs that stop touching or whose first body stops being one-way are dropped
+    one_way_contacts: HashMap<(usize, usize), bool>,
 }
 
 impl World {
@@ -21,6 +60,15 @@ impl World {
         Self {
             bodies: Vec::new(),
             gravity: nalgebra::Vector2::new(0.0, -9.81), // Default gravity pointing down
+            joints: Vec::new(),
+            body_defaults: BodyDefaults::default(),
+            restitution_threshold: 0.0,
+            n_body_gravity: None,
+            force_fields: Vec::new(),
+            time: 0.0,
+            air_density: 0.0012,
+            fluids: Vec::new(),
+            one_way_contacts: HashMap::new(),
         }
     }
 
@@ -29,34 +77,88 @@ impl World {
         self.bodies.push(body);
     }
 
+    /// Adds a joint to the world and returns its index in `joints`
+    pub fn add_joint(&mut self, joint: Joint) -> usize {
+        self.joints.push(joint);
+        self.joints.len() - 1
+    }
+
+    /// Adds a force field to the world and returns its index in `force_fields`
+    pub fn add_force_field(&mut self, field: impl ForceField + 'static) -> usize {
+        self.force_fields.push(Box::new(field));
+        self.force_fields.len() - 1
+    }
+
+    /// Adds a fluid volume to the world and returns its index in `fluids`
+    pub fn add_fluid(&mut self, fluid: FluidVolume) -> usize {
+        self.fluids.push(fluid);
+        self.fluids.len() - 1
+    }
+
     /// Updates the physics simulation by one time step
     pub fn update(&mut self, dt: f64) {
         // 1. Reset forces for all bodies
         for body in &mut self.bodies {
             body.force = nalgebra::Vector2::zeros();
+            body.torque = 0.0;
             // Keep acceleration from previous step until recalculated in body.update
         }
 
-        // 2. Apply global forces (like gravity)
-        for body in &mut self.bodies {
+        // 2. Apply global forces (gravity, force fields, air and fluids)
+        let n_body_forces = self.n_body_gravity.as_ref().map(|gravity| gravity.forces(&self.bodies));
+        for (index, body) in self.bodies.iter_mut().enumerate() {
             if let BodyType::Dynamic = body.body_type {
-                body.apply_force(self.gravity * body.mass);
+                body.apply_force(self.gravity * body.mass * body.gravity_scale);
+                if let Some(forces) = &n_body_forces {
+                    body.apply_force(forces[index]);
+                }
+                for field in &self.force_fields {
+                    if body.field_category & field.mask() != 0 {
+                        let force = field.force(body, self.time);
+                        body.apply_force(force);
+                    }
+                }
+                let air_force = aerodynamic_force(body, self.air_density);
+                body.apply_force(air_force);
+                for fluid in &self.fluids {
+                    let (force, torque) = fluid.force_and_torque(body, self.gravity);
+                    body.apply_force(force);
+                    body.apply_torque(torque);
+                }
             }
         }
 
         // 3. Update body positions/velocities (integrates forces -> acceleration -> velocity -> position)
         for body in &mut self.bodies {
-            body.update(dt);
+            body.update_with_defaults(dt, &self.body_defaults);
         }
 
-        // 4. Iteratively resolve collisions (applies impulse-based velocity changes)
+        // Decide which new contacts with one-way bodies are passing through them
+        let collisions = detect_collisions(&self.bodies);
+        update_one_way_contacts(&self.bodies, &collisions, &mut self.one_way_contacts);
+
+        // 4. Iteratively resolve collisions and joints (applies impulse-based velocity changes)
         const SOLVER_ITERATIONS: u32 = 10;
         for _ in 0..SOLVER_ITERATIONS {
-            let collisions = detect_collisions(&self.bodies);
-            if collisions.is_empty() {
+            let collisions = self.blocking_collisions();
+            if collisions.is_empty() && self.joints.is_empty() {
                 break;
             }
             resolve_collisions(self, &collisions);
+            solve_joints(&self.joints, &mut self.bodies, dt);
         }
+
+        // 5. Push apart whatever still overlaps so resting bodies do not sink
+        let collisions = self.blocking_collisions();
+        correct_positions(&mut self.bodies, &collisions);
+
+        self.time += dt;
+    }
+
+    /// Detects collisions, leaving out bodies passing through one-way bodies
+    fn blocking_collisions(&self) -> Vec<Collision> {
+        let mut collisions = detect_collisions(&self.bodies);
+        remove_one_way_passes(&mut collisions, &self.one_way_contacts);
+        collisions
     }
 }
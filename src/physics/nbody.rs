@@ -0,0 +1,246 @@
+//! Newtonian gravity between every pair of bodies, with a Barnes-Hut quadtree for large N
+
+use nalgebra::{Point2, Vector2};
+use crate::physics::bodies::Body;
+
+/// Quadtrees stop splitting at this depth, so bodies at the same position share a leaf
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Mutual gravitation between all bodies with mass, used instead of (or alongside) the
+/// uniform `World::gravity`. Static bodies attract others but do not move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NBodyGravity {
+    /// Gravitational constant G (N*m^2/kg^2)
+    pub gravitational_constant: f64,
+    /// Plummer softening length, limiting the force between bodies that come very close
+    pub softening: f64,
+    /// Barnes-Hut opening angle: a cell is treated as one mass when its size divided by its
+    /// distance is below this. Zero sums every pair exactly.
+    pub theta: f64,
+    /// With fewer bodies than this, every pair is summed exactly without building a tree
+    pub barnes_hut_threshold: usize,
+}
+
+impl NBodyGravity {
+    /// Creates unsoftened gravity with the given constant, using Barnes-Hut for 64 or more bodies
+    pub fn new(gravitational_constant: f64) -> Self {
+        Self {
+            gravitational_constant,
+            softening: 0.0,
+            theta: 0.5,
+            barnes_hut_threshold: 64,
+        }
+    }
+
+    /// Sets the softening length
+    pub fn with_softening(mut self, softening: f64) -> Self {
+        self.softening = softening;
+        self
+    }
+
+    /// Sets the Barnes-Hut opening angle
+    pub fn with_theta(mut self, theta: f64) -> Self {
+        self.theta = theta;
+        self
+    }
+
+    /// Sets the body count from which the Barnes-Hut tree is used
+    pub fn with_barnes_hut_threshold(mut self, barnes_hut_threshold: usize) -> Self {
+        self.barnes_hut_threshold = barnes_hut_threshold;
+        self
+    }
+
+    /// Gravitational force on each body from all the others, in the order of `bodies`
+    pub fn forces(&self, bodies: &[Body]) -> Vec<Vector2<f64>> {
+        if bodies.len() < self.barnes_hut_threshold {
+            return bodies
+                .iter()
+                .enumerate()
+                .map(|(i, body)| {
+                    bodies
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(_, other)| self.pull(body, other.position, other.mass))
+                        .sum()
+                })
+                .collect();
+        }
+
+        let tree = QuadTree::build(bodies);
+        (0..bodies.len()).map(|i| self.tree_force(&tree, 0, bodies, i)).collect()
+    }
+
+    /// Softened force on `body` from a point mass
+    fn pull(&self, body: &Body, position: Point2<f64>, mass: f64) -> Vector2<f64> {
+        let offset = position - body.position;
+        let distance_squared = offset.norm_squared() + self.softening * self.softening;
+        if distance_squared <= 0.0 {
+            return Vector2::zeros();
+        }
+        offset * (self.gravitational_constant * body.mass * mass / (distance_squared * distance_squared.sqrt()))
+    }
+
+    fn tree_force(&self, tree: &QuadTree, node: usize, bodies: &[Body], index: usize) -> Vector2<f64> {
+        let cell = &tree.nodes[node];
+        if cell.mass <= 0.0 {
+            return Vector2::zeros();
+        }
+        match cell.children {
+            None => cell
+                .bodies
+                .iter()
+                .filter(|&&other| other != index)
+                .map(|&other| self.pull(&bodies[index], bodies[other].position, bodies[other].mass))
+                .sum(),
+            Some(children) => {
+                let distance = (cell.center_of_mass - bodies[index].position).norm();
+                if cell.half_size * 2.0 < self.theta * distance {
+                    self.pull(&bodies[index], cell.center_of_mass, cell.mass)
+                } else {
+                    children.iter().map(|&child| self.tree_force(tree, child, bodies, index)).sum()
+                }
+            }
+        }
+    }
+}
+
+/// One square cell of the quadtree
+struct QuadNode {
+    center: Point2<f64>,
+    half_size: f64,
+    mass: f64,
+    center_of_mass: Point2<f64>,
+    /// Indices of the four child cells, once split
+    children: Option<[usize; 4]>,
+    /// Bodies held by a leaf
+    bodies: Vec<usize>,
+}
+
+impl QuadNode {
+    fn new(center: Point2<f64>, half_size: f64) -> Self {
+        Self {
+            center,
+            half_size,
+            mass: 0.0,
+            center_of_mass: center,
+            children: None,
+            bodies: Vec::new(),
+        }
+    }
+}
+
+/// Quadtree over the bodies with mass, each cell summarized by its total mass and center of mass
+struct QuadTree {
+    nodes: Vec<QuadNode>,
+}
+
+impl QuadTree {
+    fn build(bodies: &[Body]) -> Self {
+        let massive = || bodies.iter().enumerate().filter(|(_, body)| body.mass > 0.0);
+        let (min, max) = massive().fold(
+            (Point2::new(f64::INFINITY, f64::INFINITY), Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY)),
+            |(min, max), (_, body)| (min.inf(&body.position), max.sup(&body.position)),
+        );
+        let mut tree = Self { nodes: Vec::new() };
+        if min.x > max.x {
+            tree.nodes.push(QuadNode::new(Point2::origin(), 0.0));
+            return tree;
+        }
+        let half_size = ((max - min).max() / 2.0).max(1e-9);
+        tree.nodes.push(QuadNode::new(nalgebra::center(&min, &max), half_size));
+        for (index, _) in massive() {
+            tree.insert(0, index, bodies, 0);
+        }
+        tree
+    }
+
+    fn insert(&mut self, node: usize, index: usize, bodies: &[Body], depth: usize) {
+        let body = &bodies[index];
+        let cell = &mut self.nodes[node];
+        let mass = cell.mass + body.mass;
+        cell.center_of_mass = Point2::from((cell.center_of_mass.coords * cell.mass + body.position.coords * body.mass) / mass);
+        cell.mass = mass;
+
+        match cell.children {
+            Some(children) => {
+                let child = children[Self::quadrant(cell.center, body.position)];
+                self.insert(child, index, bodies, depth + 1);
+            }
+            None if cell.bodies.is_empty() || depth >= MAX_TREE_DEPTH => cell.bodies.push(index),
+            None => {
+                // Split the leaf and push its bodies down
+                let (center, quarter) = (cell.center, cell.half_size / 2.0);
+                let held = std::mem::take(&mut cell.bodies);
+                let first = self.nodes.len();
+                for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+                    self.nodes.push(QuadNode::new(center + Vector2::new(dx, dy) * quarter, quarter));
+                }
+                self.nodes[node].children = Some([first, first + 1, first + 2, first + 3]);
+                for other in held.into_iter().chain([index]) {
+                    let child = first + Self::quadrant(center, bodies[other].position);
+                    self.insert(child, other, bodies, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// Child cell index (0..4) for a position, in the order the children are created
+    fn quadrant(center: Point2<f64>, position: Point2<f64>) -> usize {
+        (position.x >= center.x) as usize + 2 * (position.y >= center.y) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::bodies::{BodyType, Material};
+
+    #[test]
+    fn test_pairwise_force() {
+        let bodies = vec![
+            Body::new_circle(Point2::new(0.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic),
+            Body::new_circle(Point2::new(3.0, 4.0), 2.0, Material::stone(), BodyType::Static),
+        ];
+        let gravity = NBodyGravity::new(2.0);
+        let forces = gravity.forces(&bodies);
+        let expected = Vector2::new(3.0, 4.0) / 5.0 * (2.0 * bodies[0].mass * bodies[1].mass / 25.0);
+        assert!((forces[0] - expected).norm() < 1e-10);
+        assert!((forces[0] + forces[1]).norm() < 1e-10);
+
+        // Softening weakens the pull at short range
+        let softened = gravity.with_softening(5.0).forces(&bodies);
+        assert!((softened[0] - expected * 25.0 / 50.0_f64.powf(1.5) * 5.0).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_barnes_hut_matches_exact_sum() {
+        // Deterministic pseudo-random cloud of bodies, including two at the same spot
+        let mut seed: u64 = 12345;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let mut bodies: Vec<Body> = (0..300)
+            .map(|_| {
+                let position = Point2::new(next() * 100.0 - 50.0, next() * 60.0 - 30.0);
+                Body::new_circle(position, 0.2 + next(), Material::stone(), BodyType::Dynamic)
+            })
+            .collect();
+        bodies.push(bodies[0].clone());
+
+        let exact = NBodyGravity::new(1.0).with_softening(0.1).with_barnes_hut_threshold(usize::MAX);
+        let tree = exact.clone().with_barnes_hut_threshold(0);
+        let exact_forces = exact.forces(&bodies);
+        let tree_forces = tree.forces(&bodies);
+        let typical = exact_forces.iter().map(|force| force.norm()).sum::<f64>() / bodies.len() as f64;
+        for (exact, approximate) in exact_forces.iter().zip(&tree_forces) {
+            assert!((exact - approximate).norm() < 0.05 * typical);
+        }
+
+        // A zero opening angle always opens cells, reproducing the exact sum
+        for (exact, opened) in exact_forces.iter().zip(tree.with_theta(0.0).forces(&bodies)) {
+            assert!((exact - opened).norm() < 1e-9 * typical);
+        }
+    }
+}
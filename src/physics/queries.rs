@@ -0,0 +1,805 @@
+//! Spatial queries against the bodies of a world
+
+use nalgebra::{Point2, Vector2};
+use crate::physics::World;
+use crate::physics::bodies::{Body, Shape};
+use crate::physics::collisions::{collide_shapes_all, compute_aabb, polygon_vertices, rounded_segment, Aabb};
+use crate::physics::gjk::{self, ConvexCore};
+
+/// Closest intersection of a ray with a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    /// Index of the body that was hit
+    pub body: usize,
+    /// Index of the child that was hit when the body has a compound shape
+    pub child: Option<usize>,
+    /// World-space point where the ray enters the shape
+    pub point: Point2<f64>,
+    /// Surface normal at the hit point, facing against the ray
+    pub normal: Vector2<f64>,
+    /// Distance travelled along the ray as a fraction of `max_distance`
+    pub fraction: f64,
+}
+
+/// First body hit by a swept shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeCastHit {
+    /// Index of the body that was hit
+    pub body: usize,
+    /// Index of the child that was hit when the body has a compound shape
+    pub child: Option<usize>,
+    /// Fraction of the translation travelled before touching
+    pub time_of_impact: f64,
+    /// World-space contact point at the time of impact
+    pub point: Point2<f64>,
+    /// Surface normal of the body that was hit, facing the swept shape
+    pub normal: Vector2<f64>,
+}
+
+/// First contact between two moving bodies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeOfImpact {
+    /// Time until the bodies touch
+    pub time: f64,
+    /// World-space contact point at that time
+    pub point: Point2<f64>,
+    /// Contact normal pointing from the first body towards the second
+    pub normal: Vector2<f64>,
+}
+
+/// Separation between two bodies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Distance {
+    /// Gap between the surfaces, negative by the penetration depth when they overlap
+    pub distance: f64,
+    /// Closest point on the first body (its deepest point inside the second when overlapping)
+    pub point_a: Point2<f64>,
+    /// Closest point on the second body (its deepest point inside the first when overlapping)
+    pub point_b: Point2<f64>,
+}
+
+/// Hit of a ray against a single shape: distance along the ray, surface normal and compound child
+struct ShapeHit {
+    distance: f64,
+    normal: Vector2<f64>,
+    child: Option<usize>,
+}
+
+impl World {
+    /// Casts a ray from `origin` along `direction` and returns the closest hit within `max_distance`.
+    /// Only bodies for which `filter(index, body)` returns true are tested.
+    ///
+    /// A ray starting inside a solid shape hits it at fraction 0 with the normal facing back along
+    /// the ray. Segments, chains and height fields are two-sided and never contain the origin.
+    pub fn raycast(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_distance: f64,
+        filter: impl Fn(usize, &Body) -> bool,
+    ) -> Option<RaycastHit> {
+        self.raycast_hits(origin, direction, max_distance, filter)
+            .into_iter()
+            .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+    }
+
+    /// Like [`World::raycast`], but returns the closest hit on every body the ray crosses,
+    /// sorted from nearest to farthest
+    pub fn raycast_all(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_distance: f64,
+        filter: impl Fn(usize, &Body) -> bool,
+    ) -> Vec<RaycastHit> {
+        let mut hits = self.raycast_hits(origin, direction, max_distance, filter);
+        hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction));
+        hits
+    }
+
+    /// Returns the bodies whose shape contains `point`.
+    ///
+    /// Height fields count as solid below their surface; segments and chains have no area and
+    /// never contain a point.
+    pub fn query_point(&self, point: Point2<f64>) -> Vec<usize> {
+        self.bodies
+            .iter()
+            .enumerate()
+            // Height fields are solid all the way down, below their bounding box
+            .filter(|(_, body)| {
+                matches!(body.shape, Shape::HeightField { .. }) || compute_aabb(&body.shape, body.position).contains(point)
+            })
+            .filter(|(_, body)| shape_contains_point(&body.shape, body.position, point))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns the bodies whose shape overlaps the box
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<usize> {
+        let size = aabb.max - aabb.min;
+        let shape = Shape::Rectangle { width: size.x, height: size.y };
+        self.query_shape(&shape, aabb.min + size / 2.0)
+    }
+
+    /// Returns the bodies that `shape`, placed at `position`, would collide with
+    pub fn query_shape(&self, shape: &Shape, position: Point2<f64>) -> Vec<usize> {
+        let bounds = compute_aabb(shape, position);
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| compute_aabb(&body.shape, body.position).overlaps(&bounds))
+            .filter(|(_, body)| !collide_shapes_all(shape, position, &body.shape, body.position).is_empty())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Sweeps `shape` from `start` along `translation` and returns the first body it would touch.
+    /// Only bodies for which `filter(index, body)` returns true are tested.
+    ///
+    /// Bodies already overlapping the shape at `start` are hit with a time of impact of 0, so
+    /// a character controller should filter out its own body.
+    pub fn shape_cast(
+        &self,
+        shape: &Shape,
+        start: Point2<f64>,
+        translation: Vector2<f64>,
+        filter: impl Fn(usize, &Body) -> bool,
+    ) -> Option<ShapeCastHit> {
+        let swept = compute_aabb(shape, start).merged(&compute_aabb(shape, start + translation));
+        let casters = convex_parts(shape, start, &swept);
+
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter(|(index, body)| filter(*index, body))
+            .filter(|(_, body)| compute_aabb(&body.shape, body.position).overlaps(&swept))
+            .flat_map(|(index, body)| {
+                let targets = convex_parts(&body.shape, body.position, &swept);
+                casters
+                    .iter()
+                    .flat_map(move |(_, caster)| {
+                        targets.clone().into_iter().filter_map(move |(child, target)| {
+                            let hit = gjk::cast(caster, translation, &target)?;
+                            Some(ShapeCastHit {
+                                body: index,
+                                child,
+                                time_of_impact: hit.fraction,
+                                point: hit.point,
+                                normal: -hit.normal,
+                            })
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact))
+    }
+
+    fn raycast_hits(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_distance: f64,
+        filter: impl Fn(usize, &Body) -> bool,
+    ) -> Vec<RaycastHit> {
+        let length = direction.norm();
+        if length < 1e-12 || max_distance <= 0.0 {
+            return Vec::new();
+        }
+        let direction = direction / length;
+
+        // There is no broad phase yet, so bodies are culled by their bounding boxes
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter(|(index, body)| filter(*index, body))
+            .filter(|(_, body)| {
+                ray_hits_aabb(&compute_aabb(&body.shape, body.position), origin, direction, max_distance)
+            })
+            .filter_map(|(index, body)| {
+                let hit = raycast_shape(&body.shape, body.position, origin, direction, max_distance)?;
+                Some(RaycastHit {
+                    body: index,
+                    child: hit.child,
+                    point: origin + direction * hit.distance,
+                    normal: hit.normal,
+                    fraction: hit.distance / max_distance,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Finds when two bodies moving at their current velocities first touch within `dt`.
+/// Fast bodies can use this to catch contacts that a single step would tunnel through.
+pub fn time_of_impact(body_a: &Body, body_b: &Body, dt: f64) -> Option<TimeOfImpact> {
+    let translation = (body_a.velocity - body_b.velocity) * dt;
+    let start = compute_aabb(&body_a.shape, body_a.position);
+    let swept = start.merged(&Aabb { min: start.min + translation, max: start.max + translation });
+    let targets = convex_parts(&body_b.shape, body_b.position, &swept);
+
+    convex_parts(&body_a.shape, body_a.position, &swept)
+        .iter()
+        .flat_map(|(_, part)| targets.iter().filter_map(|(_, target)| gjk::cast(part, translation, target)))
+        .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+        .map(|hit| {
+            let time = hit.fraction * dt;
+            // The cast ran in body_b's frame; move the point along with it
+            TimeOfImpact { time, point: hit.point + body_b.velocity * time, normal: hit.normal }
+        })
+}
+
+/// Measures the distance between two bodies of any shape, or `None` if either has no geometry
+/// (an empty compound or a chain with fewer than two vertices).
+/// Non-convex shapes are split into convex parts and the closest pair is reported.
+pub fn distance(body_a: &Body, body_b: &Body) -> Option<Distance> {
+    let everywhere = Aabb {
+        min: Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        max: Point2::new(f64::INFINITY, f64::INFINITY),
+    };
+    let parts_b = convex_parts(&body_b.shape, body_b.position, &everywhere);
+
+    convex_parts(&body_a.shape, body_a.position, &everywhere)
+        .iter()
+        .flat_map(|(_, a)| parts_b.iter().map(move |(_, b)| gjk::signed_distance(a, b)))
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+        .map(|(distance, point_a, point_b)| Distance { distance, point_a, point_b })
+}
+
+/// Splits a shape into convex pieces, tagged with their compound child index.
+/// Chain and height field edges outside `bounds` are skipped.
+fn convex_parts(shape: &Shape, position: Point2<f64>, bounds: &Aabb) -> Vec<(Option<usize>, ConvexCore)> {
+    let edge = |a: Point2<f64>, b: Point2<f64>| {
+        let core = ConvexCore { points: vec![a, b], radius: 0.0 };
+        let edge_bounds = Aabb { min: Point2::new(a.x.min(b.x), a.y.min(b.y)), max: Point2::new(a.x.max(b.x), a.y.max(b.y)) };
+        edge_bounds.overlaps(bounds).then_some((None, core))
+    };
+
+    match shape {
+        Shape::Compound { children } => children
+            .iter()
+            .enumerate()
+            .flat_map(|(index, child)| {
+                convex_parts(&child.shape, position + child.offset, bounds)
+                    .into_iter()
+                    .map(move |(_, core)| (Some(index), core))
+            })
+            .collect(),
+        Shape::Chain { vertices } => vertices
+            .windows(2)
+            .filter_map(|pair| edge(position + pair[0].coords, position + pair[1].coords))
+            .collect(),
+        Shape::HeightField { heights, spacing } => {
+            if heights.len() < 2 || *spacing <= 0.0 {
+                return Vec::new();
+            }
+            let last = heights.len() - 1;
+            let first = (((bounds.min.x - position.x) / spacing).floor().max(0.0) as usize).min(last - 1);
+            let end = (((bounds.max.x - position.x) / spacing).ceil().max(0.0) as usize)
+                .max(first + 1)
+                .min(last);
+            let point = |i: usize| position + Vector2::new(i as f64 * spacing, heights[i]);
+            (first..end).filter_map(|i| edge(point(i), point(i + 1))).collect()
+        }
+        _ => ConvexCore::from_shape(shape, position).map(|core| (None, core)).into_iter().collect(),
+    }
+}
+
+fn shape_contains_point(shape: &Shape, position: Point2<f64>, point: Point2<f64>) -> bool {
+    match shape {
+        Shape::Compound { children } => children
+            .iter()
+            .any(|child| shape_contains_point(&child.shape, position + child.offset, point)),
+        Shape::HeightField { heights, spacing } => {
+            let column = (point.x - position.x) / spacing;
+            if heights.len() < 2 || column < 0.0 || column > (heights.len() - 1) as f64 {
+                return false;
+            }
+            let i = (column.floor() as usize).min(heights.len() - 2);
+            let t = column - i as f64;
+            point.y - position.y <= heights[i] + (heights[i + 1] - heights[i]) * t
+        }
+        Shape::Segment { .. } | Shape::Chain { .. } => false,
+        _ => {
+            let Some(core) = ConvexCore::from_shape(shape, position) else {
+                return false;
+            };
+            let probe = ConvexCore { points: vec![point], radius: 0.0 };
+            match gjk::closest_points(&core, &probe) {
+                Some((closest, _)) => (closest - point).norm() <= core.radius,
+                None => true,
+            }
+        }
+    }
+}
+
+/// Slab test of a ray (unit `direction`) against a bounding box
+fn ray_hits_aabb(aabb: &Aabb, origin: Point2<f64>, direction: Vector2<f64>, max_distance: f64) -> bool {
+    let mut t_min: f64 = 0.0;
+    let mut t_max = max_distance;
+    for axis in 0..2 {
+        if direction[axis].abs() < 1e-12 {
+            if origin[axis] < aabb.min[axis] || origin[axis] > aabb.max[axis] {
+                return false;
+            }
+            continue;
+        }
+        let t1 = (aabb.min[axis] - origin[axis]) / direction[axis];
+        let t2 = (aabb.max[axis] - origin[axis]) / direction[axis];
+        t_min = t_min.max(t1.min(t2));
+        t_max = t_max.min(t1.max(t2));
+        if t_min > t_max {
+            return false;
+        }
+    }
+    true
+}
+
+/// Casts a ray (unit `direction`) against a shape placed at `position`
+fn raycast_shape(
+    shape: &Shape,
+    position: Point2<f64>,
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    match shape {
+        Shape::Compound { children } => children
+            .iter()
+            .enumerate()
+            .filter_map(|(index, child)| {
+                let hit = raycast_shape(&child.shape, position + child.offset, origin, direction, max_distance)?;
+                Some(ShapeHit { child: Some(index), ..hit })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance)),
+        Shape::Chain { vertices } => {
+            let world: Vec<Point2<f64>> = vertices.iter().map(|v| position + v.coords).collect();
+            raycast_polyline(&world, origin, direction, max_distance)
+        }
+        Shape::HeightField { heights, spacing } => {
+            if heights.len() < 2 || *spacing <= 0.0 {
+                return None;
+            }
+            // Only the columns under the ray's horizontal extent can be hit
+            let end = origin + direction * max_distance;
+            let last = heights.len() - 1;
+            // At least one column, so a vertical ray exactly on a sample still meets its edges
+            let first = (((origin.x.min(end.x) - position.x) / spacing).floor().max(0.0) as usize).min(last - 1);
+            let end_column = (((origin.x.max(end.x) - position.x) / spacing).ceil().max(0.0) as usize)
+                .max(first + 1)
+                .min(last);
+            let world: Vec<Point2<f64>> = (first..=end_column)
+                .map(|i| position + Vector2::new(i as f64 * spacing, heights[i]))
+                .collect();
+            raycast_polyline(&world, origin, direction, max_distance)
+        }
+        _ => {
+            if let Some(vertices) = polygon_vertices(shape, position) {
+                return raycast_polygon(&vertices, origin, direction, max_distance);
+            }
+            let (a, b, radius) = rounded_segment(shape, position)?;
+            raycast_rounded_segment(a, b, radius, origin, direction, max_distance)
+        }
+    }
+}
+
+/// Ray against a two-sided segment; the normal faces against the ray
+fn raycast_segment(
+    a: Point2<f64>,
+    b: Point2<f64>,
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    let edge = b - a;
+    let denominator = cross(direction, edge);
+    if denominator.abs() < 1e-12 {
+        return None;
+    }
+    let to_start = a - origin;
+    let distance = cross(to_start, edge) / denominator;
+    let along_edge = cross(to_start, direction) / denominator;
+    if !(0.0..=max_distance).contains(&distance) || !(0.0..=1.0).contains(&along_edge) {
+        return None;
+    }
+    let mut normal = Vector2::new(-edge.y, edge.x).normalize();
+    if normal.dot(&direction) > 0.0 {
+        normal = -normal;
+    }
+    Some(ShapeHit { distance, normal, child: None })
+}
+
+fn raycast_polyline(
+    world: &[Point2<f64>],
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    world
+        .windows(2)
+        .filter_map(|edge| raycast_segment(edge[0], edge[1], origin, direction, max_distance))
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+fn raycast_circle(
+    center: Point2<f64>,
+    radius: f64,
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    let offset = origin - center;
+    let c = offset.norm_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(ShapeHit { distance: 0.0, normal: -direction, child: None });
+    }
+    let b = offset.dot(&direction);
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let distance = -b - discriminant.sqrt();
+    if !(0.0..=max_distance).contains(&distance) {
+        return None;
+    }
+    let normal = (origin + direction * distance - center) / radius;
+    Some(ShapeHit { distance, normal, child: None })
+}
+
+/// Ray against circles, capsules and segments: the end caps plus the two flat sides
+fn raycast_rounded_segment(
+    a: Point2<f64>,
+    b: Point2<f64>,
+    radius: f64,
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    if radius <= 0.0 {
+        return raycast_segment(a, b, origin, direction, max_distance);
+    }
+
+    let axis = b - a;
+    let mut hits = vec![
+        raycast_circle(a, radius, origin, direction, max_distance),
+        raycast_circle(b, radius, origin, direction, max_distance),
+    ];
+    if axis.norm_squared() > 1e-12 {
+        let side = Vector2::new(-axis.y, axis.x).normalize() * radius;
+        let t = ((origin - a).dot(&axis) / axis.norm_squared()).clamp(0.0, 1.0);
+        if (origin - (a + axis * t)).norm_squared() <= radius * radius {
+            return Some(ShapeHit { distance: 0.0, normal: -direction, child: None });
+        }
+        for offset in [side, -side] {
+            hits.push(
+                raycast_segment(a + offset, b + offset, origin, direction, max_distance)
+                    .filter(|_| offset.dot(&direction) < 0.0)
+                    .map(|hit| ShapeHit { normal: offset / radius, ..hit }),
+            );
+        }
+    }
+    hits.into_iter()
+        .flatten()
+        .min_by(|x, y| x.distance.total_cmp(&y.distance))
+}
+
+/// Ray against a counter-clockwise convex polygon (Cyrus-Beck clipping)
+fn raycast_polygon(
+    vertices: &[Point2<f64>],
+    origin: Point2<f64>,
+    direction: Vector2<f64>,
+    max_distance: f64,
+) -> Option<ShapeHit> {
+    let mut t_enter: f64 = 0.0;
+    let mut t_exit = max_distance;
+    let mut normal = -direction;
+    for i in 0..vertices.len() {
+        let edge = vertices[(i + 1) % vertices.len()] - vertices[i];
+        if edge.norm_squared() < 1e-12 {
+            continue;
+        }
+        let edge_normal = Vector2::new(edge.y, -edge.x).normalize();
+        let outside = edge_normal.dot(&(origin - vertices[i]));
+        let rate = edge_normal.dot(&direction);
+        if rate.abs() < 1e-12 {
+            if outside > 0.0 {
+                return None;
+            }
+        } else if rate < 0.0 {
+            let t = -outside / rate;
+            if t > t_enter {
+                t_enter = t;
+                normal = edge_normal;
+            }
+        } else {
+            t_exit = t_exit.min(-outside / rate);
+        }
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+    Some(ShapeHit { distance: t_enter, normal, child: None })
+}
+
+fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::bodies::{BodyType, CompoundChild, Material};
+
+    fn any(_: usize, _: &Body) -> bool {
+        true
+    }
+
+    #[test]
+    fn test_raycast_hits_closest_body() {
+        let mut world = World::new();
+        world.add_body(Body::new_circle(Point2::new(10.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_rectangle(Point2::new(5.0, 0.0), 2.0, 2.0, Material::stone(), BodyType::Static));
+
+        let hit = world.raycast(Point2::new(0.0, 0.0), Vector2::new(2.0, 0.0), 20.0, any).unwrap();
+        assert_eq!(hit.body, 1);
+        assert!((hit.point - Point2::new(4.0, 0.0)).norm() < 1e-10);
+        assert!((hit.normal - Vector2::new(-1.0, 0.0)).norm() < 1e-10);
+        assert!((hit.fraction - 0.2).abs() < 1e-10);
+
+        // Filtering out the box lets the ray reach the circle
+        let hit = world.raycast(Point2::new(0.0, 0.0), Vector2::new(1.0, 0.0), 20.0, |i, _| i != 1).unwrap();
+        assert_eq!(hit.body, 0);
+        assert!((hit.point - Point2::new(9.0, 0.0)).norm() < 1e-10);
+
+        // Too short, pointing away, or with no direction at all
+        assert!(world.raycast(Point2::new(0.0, 0.0), Vector2::new(1.0, 0.0), 3.0, any).is_none());
+        assert!(world.raycast(Point2::new(0.0, 0.0), Vector2::new(-1.0, 0.0), 20.0, any).is_none());
+        assert!(world.raycast(Point2::new(0.0, 0.0), Vector2::zeros(), 20.0, any).is_none());
+
+        let hits = world.raycast_all(Point2::new(0.0, 0.0), Vector2::new(1.0, 0.0), 20.0, any);
+        assert_eq!(hits.iter().map(|h| h.body).collect::<Vec<_>>(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_raycast_every_shape() {
+        let down = Vector2::new(0.0, -1.0);
+        let cast = |body: Body| {
+            let mut world = World::new();
+            world.add_body(body);
+            world.raycast(Point2::new(0.3, 10.0), down, 20.0, any)
+        };
+        let expect = |hit: Option<RaycastHit>, y: f64, normal: Vector2<f64>| {
+            let hit = hit.unwrap();
+            assert!((hit.point.y - y).abs() < 1e-9, "hit at {} instead of {}", hit.point.y, y);
+            assert!((hit.normal - normal).norm() < 1e-9, "normal {} instead of {}", hit.normal, normal);
+        };
+        let up = Vector2::new(0.0, 1.0);
+
+        expect(cast(Body::new_circle(Point2::new(0.3, 0.0), 1.0, Material::wood(), BodyType::Dynamic)), 1.0, up);
+        expect(cast(Body::new_capsule(Point2::new(0.0, 0.0), 1.0, 0.5, Material::wood(), BodyType::Dynamic)), 1.4, Vector2::new(0.6, 0.8));
+        expect(
+            cast(Body::new_polygon(
+                Point2::new(0.0, 0.0),
+                vec![Point2::new(-1.0, 0.0), Point2::new(1.0, 0.0), Point2::new(0.0, 2.0)],
+                Material::wood(),
+                BodyType::Dynamic,
+            )
+            .unwrap()),
+            1.4,
+            Vector2::new(2.0, 1.0).normalize(),
+        );
+        expect(
+            cast(Body::new_segment(Point2::new(0.0, 0.0), Point2::new(-1.0, -1.0), Point2::new(1.0, 1.0), Material::stone())),
+            0.3,
+            Vector2::new(-1.0, 1.0).normalize(),
+        );
+        expect(
+            cast(Body::new_chain(
+                Point2::new(0.0, 0.0),
+                vec![Point2::new(-1.0, 0.0), Point2::new(0.0, 1.0), Point2::new(1.0, 0.0)],
+                Material::stone(),
+            )),
+            0.7,
+            Vector2::new(1.0, 1.0).normalize(),
+        );
+        expect(
+            cast(Body::new_height_field(Point2::new(-2.0, 0.0), vec![0.0, 1.0, 3.0, 1.0], 1.0, Material::stone())),
+            2.4,
+            Vector2::new(2.0, 1.0).normalize(),
+        );
+        let compound = Body::new_compound(
+            Point2::new(0.0, 0.0),
+            vec![
+                CompoundChild::new(Vector2::new(0.0, 0.0), Shape::Rectangle { width: 4.0, height: 1.0 }),
+                CompoundChild::new(Vector2::new(3.0, 2.0), Shape::Circle { radius: 0.5 }),
+            ],
+            Material::wood(),
+            BodyType::Dynamic,
+        );
+        let hit = cast(compound).unwrap();
+        assert_eq!(hit.child, Some(0));
+        assert!((hit.point.y - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_vertical_queries_on_height_field_samples() {
+        let mut world = World::new();
+        world.add_body(Body::new_height_field(Point2::new(0.0, 0.0), vec![0.0, 1.0, 0.5, 2.0], 1.0, Material::stone()));
+        let down = Vector2::new(0.0, -1.0);
+
+        // Straight down exactly on a sample column, and on the last sample
+        for (x, y) in [(1.0, 1.0), (3.0, 2.0), (1.1, 0.95)] {
+            let hit = world.raycast(Point2::new(x, 5.0), down, 10.0, any).unwrap();
+            assert!((hit.point.y - y).abs() < 1e-9, "hit at {} instead of {}", hit.point.y, y);
+        }
+
+        // A vertical sweep has zero width but still covers the edges next to its column
+        let sweep = Aabb { min: Point2::new(1.0, -5.0), max: Point2::new(1.0, 5.0) };
+        let terrain = &world.bodies[0];
+        assert_eq!(convex_parts(&terrain.shape, terrain.position, &sweep).len(), 1);
+        let sweep = Aabb { min: Point2::new(3.0, -5.0), max: Point2::new(3.0, 5.0) };
+        assert_eq!(convex_parts(&terrain.shape, terrain.position, &sweep).len(), 1);
+    }
+
+    #[test]
+    fn test_raycast_from_inside() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, 0.0), 2.0, 2.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_segment(Point2::new(0.0, 0.0), Point2::new(-1.0, 0.0), Point2::new(1.0, 0.0), Material::stone()));
+
+        // The solid box blocks immediately, the segment behind the ray start is ignored
+        let hit = world.raycast(Point2::new(0.0, 0.5), Vector2::new(0.0, 1.0), 5.0, any).unwrap();
+        assert_eq!(hit.body, 0);
+        assert_eq!(hit.fraction, 0.0);
+        assert!((hit.normal - Vector2::new(0.0, -1.0)).norm() < 1e-10);
+
+        let hits = world.raycast_all(Point2::new(0.0, 0.5), Vector2::new(0.0, -1.0), 5.0, any);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[1].body, 1);
+        assert!((hits[1].fraction - 0.1).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_query_point() {
+        let mut world = World::new();
+        world.add_body(Body::new_circle(Point2::new(0.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_capsule(Point2::new(5.0, 0.0), 1.0, 0.5, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_height_field(Point2::new(-10.0, -5.0), vec![0.0, 2.0, 0.0], 10.0, Material::stone()));
+        world.add_body(Body::new_segment(Point2::new(0.0, 0.0), Point2::new(-3.0, 0.0), Point2::new(3.0, 0.0), Material::stone()));
+
+        assert_eq!(world.query_point(Point2::new(0.5, 0.5)), vec![0]);
+        assert_eq!(world.query_point(Point2::new(5.3, 1.2)), vec![1]);
+        assert!(world.query_point(Point2::new(5.5, 1.5)).is_empty());
+        // Under the terrain slope at a quarter of the first column
+        assert_eq!(world.query_point(Point2::new(-7.5, -4.6)), vec![2]);
+        assert!(world.query_point(Point2::new(-7.5, -4.4)).is_empty());
+        // Below the lowest sample is still inside, beyond either end is not
+        assert_eq!(world.query_point(Point2::new(-7.5, -50.0)), vec![2]);
+        assert!(world.query_point(Point2::new(-10.5, -50.0)).is_empty());
+        assert!(world.query_point(Point2::new(2.5, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn test_query_aabb_and_shape() {
+        let mut world = World::new();
+        world.add_body(Body::new_circle(Point2::new(0.0, 0.0), 1.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_rectangle(Point2::new(4.0, 0.0), 2.0, 2.0, Material::wood(), BodyType::Dynamic));
+        world.add_body(Body::new_chain(
+            Point2::new(0.0, -3.0),
+            vec![Point2::new(-5.0, 0.0), Point2::new(5.0, 0.0)],
+            Material::stone(),
+        ));
+
+        // The box corner reaches into the circle's bounding box but not the circle itself
+        let aabb = Aabb { min: Point2::new(0.8, 0.8), max: Point2::new(3.5, 2.0) };
+        assert_eq!(world.query_aabb(&aabb), vec![1]);
+        let aabb = Aabb { min: Point2::new(-1.0, -4.0), max: Point2::new(1.0, -2.5) };
+        assert_eq!(world.query_aabb(&aabb), vec![2]);
+
+        // Blast radius around a point between the circle and the box
+        let blast = Shape::Circle { radius: 1.5 };
+        assert_eq!(world.query_shape(&blast, Point2::new(2.0, 0.0)), vec![0, 1]);
+        assert!(world.query_shape(&blast, Point2::new(0.0, 4.0)).is_empty());
+    }
+
+    #[test]
+    fn test_shape_cast() {
+        let mut world = World::new();
+        world.add_body(Body::new_rectangle(Point2::new(0.0, -1.0), 20.0, 2.0, Material::stone(), BodyType::Static));
+        world.add_body(Body::new_rectangle(Point2::new(5.0, 2.0), 2.0, 4.0, Material::stone(), BodyType::Static));
+        let capsule = Shape::Capsule { half_length: 0.5, radius: 0.5 };
+
+        // Walking right along the floor the capsule reaches the wall, not the floor it touches
+        let start = Point2::new(0.0, 1.0 + 1e-3);
+        let hit = world.shape_cast(&capsule, start, Vector2::new(10.0, 0.0), any).unwrap();
+        assert_eq!(hit.body, 1);
+        assert!((hit.time_of_impact - 0.35).abs() < 1e-5);
+        assert!((hit.normal - Vector2::new(-1.0, 0.0)).norm() < 1e-9);
+        assert!((hit.point.x - 4.0).abs() < 1e-5);
+
+        // Falling from above lands on the floor
+        let hit = world.shape_cast(&capsule, Point2::new(0.0, 5.0), Vector2::new(0.0, -8.0), any).unwrap();
+        assert_eq!(hit.body, 0);
+        assert!((hit.time_of_impact - 0.5).abs() < 1e-5);
+        assert!((hit.point - Point2::new(0.0, 0.0)).norm() < 1e-5);
+
+        assert!(world.shape_cast(&capsule, start, Vector2::new(2.0, 0.0), any).is_none());
+        assert!(world.shape_cast(&capsule, start, Vector2::new(10.0, 0.0), |i, _| i != 1).is_none());
+    }
+
+    #[test]
+    fn test_shape_cast_against_terrain() {
+        let mut world = World::new();
+        world.add_body(Body::new_height_field(Point2::new(0.0, 0.0), vec![0.0, 0.0, 0.0, 2.0, 4.0], 2.0, Material::stone()));
+        world.add_body(Body::new_chain(
+            Point2::new(0.0, 0.0),
+            vec![Point2::new(-10.0, 5.0), Point2::new(-10.0, 0.0), Point2::new(0.0, 0.0)],
+            Material::stone(),
+        ));
+        let ball = Shape::Circle { radius: 0.5 };
+
+        // Rolling right hits the slope that starts at x = 4
+        let hit = world.shape_cast(&ball, Point2::new(1.0, 0.5 + 1e-3), Vector2::new(10.0, 0.0), any).unwrap();
+        assert_eq!(hit.body, 0);
+        assert!((hit.normal - Vector2::new(-1.0, 1.0).normalize()).norm() < 1e-3);
+
+        // Rolling left hits the chain's wall
+        let hit = world.shape_cast(&ball, Point2::new(-1.0, 1.0), Vector2::new(-20.0, 0.0), any).unwrap();
+        assert_eq!(hit.body, 1);
+        assert!((hit.time_of_impact - 8.5 / 20.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_time_of_impact() {
+        let mut bullet = Body::new_circle(Point2::new(-5.0, 0.0), 0.1, Material::stone(), BodyType::Dynamic);
+        bullet.velocity = Vector2::new(600.0, 0.0);
+        let mut target = Body::new_circle(Point2::new(5.0, 0.0), 0.4, Material::wood(), BodyType::Dynamic);
+        target.velocity = Vector2::new(-400.0, 0.0);
+
+        // The gap of 9.5 closes at 1000 per second, well inside one 60 Hz step
+        let toi = time_of_impact(&bullet, &target, 1.0 / 60.0).unwrap();
+        assert!((toi.time - 0.0095).abs() < 1e-8);
+        assert!((toi.normal - Vector2::new(1.0, 0.0)).norm() < 1e-9);
+        assert!((toi.point - Point2::new(0.8, 0.0)).norm() < 1e-5);
+
+        let wall = Body::new_segment(Point2::new(0.0, 0.0), Point2::new(0.0, -1.0), Point2::new(0.0, 1.0), Material::stone());
+        assert!(time_of_impact(&bullet, &wall, 1.0 / 60.0).is_some());
+        assert!(time_of_impact(&bullet, &wall, 1.0 / 240.0).is_none());
+    }
+
+    #[test]
+    fn test_distance() {
+        let circle = |x: f64, y: f64| Body::new_circle(Point2::new(x, y), 1.0, Material::wood(), BodyType::Dynamic);
+        let crate_box = Body::new_rectangle(Point2::new(0.0, 0.0), 2.0, 2.0, Material::wood(), BodyType::Dynamic);
+
+        let result = distance(&crate_box, &circle(4.0, 0.5)).unwrap();
+        assert!((result.distance - 2.0).abs() < 1e-10);
+        assert!((result.point_a - Point2::new(1.0, 0.5)).norm() < 1e-10);
+        assert!((result.point_b - Point2::new(3.0, 0.5)).norm() < 1e-10);
+
+        // Diagonal from the box corner
+        let result = distance(&crate_box, &circle(4.0, 5.0)).unwrap();
+        assert!((result.distance - 4.0).abs() < 1e-10);
+        assert!((result.point_b - Point2::new(3.4, 4.2)).norm() < 1e-10);
+
+        // Overlapping by 0.5: negative distance and the deepest points
+        let result = distance(&circle(0.0, 0.0), &circle(1.5, 0.0)).unwrap();
+        assert!((result.distance + 0.5).abs() < 1e-10);
+        assert!((result.point_a - Point2::new(1.0, 0.0)).norm() < 1e-10);
+        assert!((result.point_b - Point2::new(0.5, 0.0)).norm() < 1e-10);
+        let result = distance(&crate_box, &circle(0.0, 1.5)).unwrap();
+        assert!((result.distance + 0.5).abs() < 1e-10);
+
+        // Non-convex shapes use their closest part
+        let terrain = Body::new_chain(
+            Point2::new(0.0, 0.0),
+            vec![Point2::new(-5.0, -3.0), Point2::new(0.0, -3.0), Point2::new(5.0, 2.0)],
+            Material::stone(),
+        );
+        let result = distance(&terrain, &circle(-2.0, 0.0)).unwrap();
+        assert!((result.distance - 2.0).abs() < 1e-10);
+        assert!((result.point_a - Point2::new(-2.0, -3.0)).norm() < 1e-10);
+        let result = distance(&terrain, &circle(5.0, -2.0)).unwrap();
+        assert!((result.distance - (4.0 / 2.0f64.sqrt() - 1.0)).abs() < 1e-10);
+        assert!((result.point_a - Point2::new(3.0, 0.0)).norm() < 1e-10);
+    }
+}